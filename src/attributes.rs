@@ -8,28 +8,158 @@ pub mod action;
 pub mod app;
 pub mod category;
 pub mod command;
+pub mod date;
 pub mod event;
 pub mod notification;
 
 use nom::{
-    multi::count,
-    number::complete::{le_u16, le_u8},
+    bytes::streaming::take,
+    number::streaming::le_u16,
     IResult,
 };
-use std::fmt::Debug;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::fmt::{self, Debug};
 
-use self::{notification::NotificationAttributeID, app::AppAttributeID};
+#[cfg(feature = "alloc")]
+use self::app::AppAttributeID;
+use self::{notification::NotificationAttributeID, date::DateTime};
+use crate::decode::Decode;
+#[cfg(feature = "alloc")]
+use crate::encode::{display_len, to_vec};
+use crate::encode::{check_length, Encode, Writer};
+#[cfg(feature = "alloc")]
+use crate::error::utf8;
+use crate::error::{utf8_str, Error};
+
+/// The typed value of a `NotificationAttribute`.
+///
+/// The variant is picked from the attribute's `NotificationAttributeID`, values that don't
+/// match the format ANCS specifies for their attribute are kept as `Text` so that the raw
+/// string, available through `Display`, is always exactly what was received.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NotificationAttributeValue {
+    Text(String),
+    /// The value of a `NotificationAttributeID::Date` attribute.
+    Date(DateTime),
+    /// The value of a `NotificationAttributeID::MessageSize` attribute.
+    Size(u32),
+    /// The value of a `NotificationAttributeID::PositiveActionLabel` or `NegativeActionLabel` attribute.
+    ActionLabel(String),
+}
+
+#[cfg(feature = "alloc")]
+impl NotificationAttributeValue {
+    /// Creates the typed value of an attribute from its raw string
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::NotificationAttributeValue;
+    /// # use ancs::attributes::date::DateTime;
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// let date = NotificationAttributeValue::from_raw(NotificationAttributeID::Date, "20240229T130500".to_string());
+    /// let size = NotificationAttributeValue::from_raw(NotificationAttributeID::MessageSize, "042".to_string());
+    ///
+    /// assert_eq!(date, NotificationAttributeValue::Date(DateTime::new(2024, 2, 29, 13, 5, 0).unwrap()));
+    /// assert_eq!(size, NotificationAttributeValue::Text("042".to_string()));
+    /// ```
+    pub fn from_raw(id: NotificationAttributeID, raw: String) -> NotificationAttributeValue {
+        match id {
+            NotificationAttributeID::Date => match raw.parse() {
+                Ok(date) => NotificationAttributeValue::Date(date),
+                Err(_) => NotificationAttributeValue::Text(raw),
+            },
+            // Only canonical integers are typed so the value encodes back to the same string.
+            NotificationAttributeID::MessageSize => match raw.parse::<u32>() {
+                Ok(size) if size.to_string() == raw => NotificationAttributeValue::Size(size),
+                _ => NotificationAttributeValue::Text(raw),
+            },
+            NotificationAttributeID::PositiveActionLabel | NotificationAttributeID::NegativeActionLabel => {
+                NotificationAttributeValue::ActionLabel(raw)
+            }
+            _ => NotificationAttributeValue::Text(raw),
+        }
+    }
+
+    /// Returns the value as a `&str` if it is textual.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NotificationAttributeValue::Text(value) => Some(value),
+            NotificationAttributeValue::ActionLabel(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_date(&self) -> Option<DateTime> {
+        match self {
+            NotificationAttributeValue::Date(date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    pub fn as_size(&self) -> Option<u32> {
+        match self {
+            NotificationAttributeValue::Size(size) => Some(*size),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for NotificationAttributeValue {
+    /// Formats a `NotificationAttributeValue` as the raw string sent over ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::NotificationAttributeValue;
+    /// let size = NotificationAttributeValue::Size(42);
+    ///
+    /// assert_eq!(size.to_string(), "42");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotificationAttributeValue::Text(value) => f.write_str(value),
+            NotificationAttributeValue::Date(date) => write!(f, "{}", date),
+            NotificationAttributeValue::Size(size) => write!(f, "{}", size),
+            NotificationAttributeValue::ActionLabel(value) => f.write_str(value),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for NotificationAttributeValue {
+    fn from(original: String) -> NotificationAttributeValue {
+        NotificationAttributeValue::Text(original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&str> for NotificationAttributeValue {
+    fn from(original: &str) -> NotificationAttributeValue {
+        NotificationAttributeValue::Text(original.to_string())
+    }
+}
 
 /// The `NotificationAttribute` type. See [the module level documentation](index.html) for more.
+#[cfg(feature = "alloc")]
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NotificationAttribute {
     pub id: NotificationAttributeID, 
     pub length: u16, 
-    pub value: Option<String>
+    pub value: Option<NotificationAttributeValue>
 }
 
-impl From<NotificationAttribute> for Vec<u8> {
-    /// Converts a `NotificationAttribute` to a `Vec<u8>`:
+#[cfg(feature = "alloc")]
+impl TryFrom<NotificationAttribute> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `NotificationAttribute` to a `Vec<u8>`:
     /// 
     /// # Examples
     /// ```
@@ -41,10 +171,10 @@ impl From<NotificationAttribute> for Vec<u8> {
     /// let attribute: NotificationAttribute = NotificationAttribute {
     ///    id: attribute_id,
     ///    length: attribute_length,
-    ///    value: Some(attribute_data)
+    ///    value: Some(attribute_data.into())
     /// };
     /// 
-    /// let converted_bytes: Vec<u8> = attribute.into();
+    /// let converted_bytes: Vec<u8> = attribute.try_into().unwrap();
     /// 
     /// assert_eq!(u8::MIN, converted_bytes[0]); // Identifier for attribute
     /// assert_eq!(4, converted_bytes[1]); // Length of attribute
@@ -54,29 +184,49 @@ impl From<NotificationAttribute> for Vec<u8> {
     /// assert_eq!(115, converted_bytes[5]); // s string char
     /// assert_eq!(116, converted_bytes[6]); // t string char strings are not NULL terminated so this is the end
     /// ```
-    fn from(original: NotificationAttribute) -> Vec<u8> {
-        let mut vec: Vec<u8> = Vec::new();
+    ///
+    /// The declared `length` must match the length of the value:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::NotificationAttribute;
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// let attribute: NotificationAttribute = NotificationAttribute {
+    ///    id: NotificationAttributeID::Title,
+    ///    length: 2,
+    ///    value: Some("test".into())
+    /// };
+    ///
+    /// let result: Result<Vec<u8>, Error> = attribute.try_into();
+    ///
+    /// assert_eq!(result, Err(Error::LengthMismatch { expected: 2, actual: 4 }));
+    /// ```
+    fn try_from(original: NotificationAttribute) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
 
-        let id: u8 = original.id.into();
-        let length: [u8; 2] = original.length.to_le_bytes();
-        let attribute: Option<Vec<u8>> = match original.value {
-            Some(value) => { Some(value.into_bytes()) },
-            None => None,
-        };
+#[cfg(feature = "alloc")]
+impl Encode for NotificationAttribute {
+    fn encoded_len(&self) -> usize {
+        3 + self.value.as_ref().map(display_len).unwrap_or(0)
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        check_length(self.length, self.encoded_len() - 3)?;
 
-        vec.push(id);
-        vec.extend(length.to_vec());
+        let mut writer = Writer::new(buf);
+        writer.put_u8(self.id.into())?;
+        writer.put(&self.length.to_le_bytes())?;
 
-        // If the attribute's value isn't null we add it to our bytes.
-        match attribute {
-            Some(attribute) => { vec.extend(attribute)},
-            None => (),
-        };
+        if let Some(value) = &self.value {
+            writer.put_display(value)?;
+        }
 
-        return vec;
+        Ok(writer.finish())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl NotificationAttribute {
     /// Attempts to parse a `NotificationAttribute` from a `&[u8]`
     /// 
@@ -93,39 +243,178 @@ impl NotificationAttribute {
     /// // Validate that all bytes were parsed per ANCS Standard
     /// assert_eq!(attribute.id, NotificationAttributeID::AppIdentifier);
     /// assert_eq!(attribute.length, 4);
-    /// assert_eq!(attribute.value, Some("test".to_string()));
+    /// assert_eq!(attribute.value, Some("test".into()));
     /// 
     /// // Validate all remaining bytes are the same
     /// assert_eq!(bytes.len(), 1);
     /// assert_eq!(bytes, [0]);
     /// ```
+    ///
+    /// Values are typed according to their attribute:
+    /// ```
+    /// # use ancs::attributes::{NotificationAttribute, NotificationAttributeValue};
+    /// # use ancs::attributes::date::DateTime;
+    /// let bytes: Vec<u8> = vec![5, 15, 0, 50, 48, 50, 52, 48, 50, 50, 57, 84, 49, 51, 48, 53, 48, 48];
+    /// let (_, attribute) = NotificationAttribute::parse(&bytes).unwrap();
+    /// let value = attribute.value.unwrap();
+    ///
+    /// assert_eq!(value.as_date(), Some(DateTime::new(2024, 2, 29, 13, 5, 0).unwrap()));
+    /// assert_eq!(value.to_string(), "20240229T130500");
+    /// ```
+    ///
+    /// Malformed values are reported as an `Error` instead of panicking:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::NotificationAttribute;
+    /// let bytes: Vec<u8> = vec![0, 4, 0, 116, 101, 255, 116];
+    ///
+    /// assert_eq!(NotificationAttribute::parse(&bytes), Err(nom::Err::Failure(Error::InvalidUtf8 { offset: 2 })));
+    /// assert_eq!(NotificationAttribute::parse(&bytes[..5]), Err(nom::Err::Error(Error::Truncated)));
+    /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], NotificationAttribute> {
-        let (i, id) = notification::NotificationAttributeID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], NotificationAttribute, Error> {
+        NotificationAttribute::decode(i)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for NotificationAttribute {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], NotificationAttribute, Error> {
+        let (i, attribute) = NotificationAttributeRef::decode_streaming(i)?;
+
+        Ok((i, attribute.to_owned()))
+    }
+}
+
+/// A `NotificationAttribute` borrowing its value from the buffer it was parsed from.
+///
+/// Parsing a `NotificationAttributeRef` never allocates, making it suitable for handling
+/// Data Source notifications at a high rate or on targets without a heap.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotificationAttributeRef<'a> {
+    pub id: NotificationAttributeID,
+    pub length: u16,
+    pub value: &'a str,
+}
+
+impl<'a> NotificationAttributeRef<'a> {
+    /// Attempts to parse a `NotificationAttributeRef` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::NotificationAttributeRef;
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// let bytes: [u8; 8] = [0, 4, 0, 116, 101, 115, 116, 0];
+    /// let (bytes, attribute) = NotificationAttributeRef::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(attribute.id, NotificationAttributeID::AppIdentifier);
+    /// assert_eq!(attribute.length, 4);
+    /// assert_eq!(attribute.value, "test");
+    /// assert_eq!(bytes, [0]);
+    /// ```
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], NotificationAttributeRef<'a>, Error> {
+        NotificationAttributeRef::decode(i)
+    }
+
+    /// Returns the value of a `NotificationAttributeID::Date` attribute.
+    pub fn date(self) -> Option<DateTime> {
+        match self.id {
+            NotificationAttributeID::Date => self.value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `NotificationAttributeID::MessageSize` attribute.
+    pub fn size(self) -> Option<u32> {
+        match self.id {
+            NotificationAttributeID::MessageSize => self.value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Converts a `NotificationAttributeRef` to an owned `NotificationAttribute`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::{NotificationAttribute, NotificationAttributeRef};
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// let bytes: [u8; 7] = [1, 4, 0, 116, 101, 115, 116];
+    /// let (_, attribute) = NotificationAttributeRef::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(attribute.to_owned(), NotificationAttribute {
+    ///     id: NotificationAttributeID::Title,
+    ///     length: 4,
+    ///     value: Some("test".into()),
+    /// });
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_owned(self) -> NotificationAttribute {
+        NotificationAttribute {
+            id: self.id,
+            length: self.length,
+            value: Some(NotificationAttributeValue::from_raw(self.id, self.value.to_string())),
+        }
+    }
+}
+
+impl<'a> Decode<'a> for NotificationAttributeRef<'a> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], NotificationAttributeRef<'a>, Error> {
+        let (i, id) = NotificationAttributeID::decode_streaming(i)?;
         let (i, length) = le_u16(i)?;
-        let (i, attribute) = count(le_u8, length.into())(i)?;
+        let (i, attribute) = take(length)(i)?;
+        let value = utf8_str(attribute).map_err(nom::Err::Failure)?;
 
-        Ok((
-            i,
-            NotificationAttribute {
-                id: notification::NotificationAttributeID::try_from(id).unwrap(),
-                length: length,
-                value: Some(String::from_utf8(attribute).unwrap()),
-            },
-        ))
+        Ok((i, NotificationAttributeRef { id, length, value }))
+    }
+}
+
+impl Encode for NotificationAttributeRef<'_> {
+    /// Returns the exact number of bytes `encode_into` writes
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Encode;
+    /// # use ancs::attributes::NotificationAttributeRef;
+    /// let bytes: [u8; 7] = [1, 4, 0, 116, 101, 115, 116];
+    /// let (_, attribute) = NotificationAttributeRef::parse(&bytes).unwrap();
+    /// let mut buf = [0_u8; 7];
+    ///
+    /// assert_eq!(attribute.encoded_len(), 7);
+    /// assert_eq!(attribute.encode_into(&mut buf), Ok(7));
+    /// assert_eq!(buf, bytes);
+    /// ```
+    fn encoded_len(&self) -> usize {
+        3 + self.value.len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        check_length(self.length, self.value.len())?;
+
+        let mut writer = Writer::new(buf);
+        writer.put_u8(self.id.into())?;
+        writer.put(&self.length.to_le_bytes())?;
+        writer.put(self.value.as_bytes())?;
+
+        Ok(writer.finish())
     }
 }
 
 /// The `AppAttribute` type. See [the module level documentation](index.html) for more.
+#[cfg(feature = "alloc")]
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AppAttribute {
     pub id: AppAttributeID, 
     pub length: u16, 
     pub value: Option<String>
 }
 
-impl From<AppAttribute> for Vec<u8> {
-    /// Converts a `AppAttribute` to a `Vec<u8>`:
+#[cfg(feature = "alloc")]
+impl TryFrom<AppAttribute> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `AppAttribute` to a `Vec<u8>`:
     /// 
     /// # Examples
     /// ```
@@ -140,7 +429,7 @@ impl From<AppAttribute> for Vec<u8> {
     ///    value: Some(attribute_data)
     /// };
     /// 
-    /// let converted_bytes: Vec<u8> = attribute.into();
+    /// let converted_bytes: Vec<u8> = attribute.try_into().unwrap();
     /// 
     /// assert_eq!(u8::MIN, converted_bytes[0]); // Identifier for attribute
     /// assert_eq!(4, converted_bytes[1]); // Length of attribute
@@ -150,29 +439,31 @@ impl From<AppAttribute> for Vec<u8> {
     /// assert_eq!(115, converted_bytes[5]); // s string char
     /// assert_eq!(116, converted_bytes[6]); // t string char strings are not NULL terminated so this is the end
     /// ```
-    fn from(original: AppAttribute) -> Vec<u8> {
-        let mut vec: Vec<u8> = Vec::new();
+    fn try_from(original: AppAttribute) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
 
-        let id: u8 = original.id.into();
-        let length: [u8; 2] = original.length.to_le_bytes();
-        let attribute: Option<Vec<u8>> = match original.value {
-            Some(value) => { Some(value.into_bytes()) },
-            None => None,
-        };
+#[cfg(feature = "alloc")]
+impl Encode for AppAttribute {
+    fn encoded_len(&self) -> usize {
+        3 + self.value.as_ref().map(String::len).unwrap_or(0)
+    }
 
-        vec.push(id);
-        vec.extend(length.to_vec());
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let value = self.value.as_deref().unwrap_or_default();
+        check_length(self.length, value.len())?;
 
-        // If the attribute's value isn't null we add it to our bytes.
-        match attribute {
-            Some(attribute) => { vec.extend(attribute)},
-            None => (),
-        };
+        let mut writer = Writer::new(buf);
+        writer.put_u8(self.id.into())?;
+        writer.put(&self.length.to_le_bytes())?;
+        writer.put(value.as_bytes())?;
 
-        return vec;
+        Ok(writer.finish())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AppAttribute {
     /// Attempts to parse a `AppAttribute` from a `&[u8]`
     /// 
@@ -196,17 +487,25 @@ impl AppAttribute {
     /// assert_eq!(bytes, [0]);
     /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], AppAttribute> {
-        let (i, id) = app::AppAttributeID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], AppAttribute, Error> {
+        AppAttribute::decode(i)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for AppAttribute {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], AppAttribute, Error> {
+        let (i, id) = app::AppAttributeID::decode_streaming(i)?;
         let (i, length) = le_u16(i)?;
-        let (i, attribute) = count(le_u8, length.into())(i)?;
+        let (i, attribute) = take(length)(i)?;
+        let value = utf8(attribute).map_err(nom::Err::Failure)?;
 
         Ok((
             i,
             AppAttribute {
-                id: app::AppAttributeID::try_from(id).unwrap(),
-                length: length,
-                value: Some(String::from_utf8(attribute).unwrap()),
+                id,
+                length,
+                value: Some(value),
             },
         ))
     }
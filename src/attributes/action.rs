@@ -1,9 +1,21 @@
-use nom::{error::ParseError, number::complete::le_u8, IResult};
+use nom::{number::streaming::le_u8, IResult};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use core::hash::{Hash, Hasher};
+
+use crate::decode::Decode;
+use crate::Error;
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+#[repr(u8)]
 pub enum ActionID {
     Positive = 0,
     Negative = 1,
+    /// An ID that isn't defined by the ANCS specification, kept so it can be passed on unchanged.
+    ///
+    /// `From<u8>` only returns it for undefined IDs, one holding a defined ID anyway
+    /// compares equal to and serializes as the variant for that ID.
+    Unknown(u8),
 }
 
 impl From<ActionID> for u8 {
@@ -20,32 +32,53 @@ impl From<ActionID> for u8 {
         match original {
             ActionID::Positive => 0,
             ActionID::Negative => 1,
+            ActionID::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for ActionID {
-    type Error = ActionIDError;
-
-    /// Attempts to convert a `u8` to a `ActionID`
+impl From<u8> for ActionID {
+    /// Converts a `u8` to a `ActionID`, IDs that aren't defined by ANCS become `ActionID::Unknown`
     ///
     /// # Examples
     /// ```
     /// # use ancs::attributes::action::ActionID;
-    /// let action_id: ActionID = ActionID::try_from(0).unwrap();
-    ///
-    /// assert_eq!(ActionID::Positive, action_id);
+    /// assert_eq!(ActionID::from(0), ActionID::Positive);
+    /// assert_eq!(ActionID::from(200), ActionID::Unknown(200));
+    /// assert_eq!(u8::from(ActionID::Unknown(200)), 200);
     /// ```
-    ///
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
+    fn from(original: u8) -> ActionID {
         match original {
-            0 => Ok(ActionID::Positive),
-            1 => Ok(ActionID::Negative),
-            _ => Err(ActionIDError),
+            0 => ActionID::Positive,
+            1 => ActionID::Negative,
+            _ => ActionID::Unknown(original),
         }
     }
 }
 
+impl PartialEq for ActionID {
+    /// Compares two IDs by their binary representation, so an `Unknown` holding a defined
+    /// ID equals the variant for that ID
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::action::ActionID;
+    /// assert_eq!(ActionID::Unknown(1), ActionID::Negative);
+    /// assert_ne!(ActionID::Unknown(200), ActionID::Unknown(201));
+    /// ```
+    fn eq(&self, other: &ActionID) -> bool {
+        u8::from(*self) == u8::from(*other)
+    }
+}
+
+impl Eq for ActionID {}
+
+impl Hash for ActionID {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+    }
+}
+
 impl ActionID {
     /// Attempts to parse a `ActionID` from a `&[u8]`
     ///
@@ -58,18 +91,38 @@ impl ActionID {
     /// assert_eq!(ActionID::Positive, action_id);
     /// ```
     ///
-    pub fn parse(i: &[u8]) -> IResult<&[u8], ActionID> {
-        let (i, action_id) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], ActionID, Error> {
+        ActionID::decode(i)
+    }
+
+    /// Attempts to parse a `ActionID` from a `&[u8]`, rejecting IDs that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::action::ActionID;
+    /// assert_eq!(ActionID::parse_strict(&[0]), Ok((&[][..], ActionID::Positive)));
+    /// assert_eq!(ActionID::parse_strict(&[200]), Err(nom::Err::Failure(Error::UnknownActionID(200))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], ActionID, Error> {
+        let (i, action_id) = ActionID::parse(i)?;
 
-        match ActionID::try_from(action_id) {
-            Ok(action_id) => Ok((i, action_id)),
-            Err(_) => Err(nom::Err::Failure(ParseError::from_error_kind(
-                i,
-                nom::error::ErrorKind::Fail,
-            ))),
+        match action_id {
+            ActionID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownActionID(id))),
+            action_id => Ok((i, action_id)),
         }
     }
+
+    /// Returns `true` if this ID isn't defined by the ANCS specification.
+    pub fn is_unknown(&self) -> bool {
+        matches!(ActionID::from(u8::from(*self)), ActionID::Unknown(_))
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct ActionIDError;
+impl<'a> Decode<'a> for ActionID {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], ActionID, Error> {
+        let (i, action_id) = le_u8(i)?;
+
+        Ok((i, ActionID::from(action_id)))
+    }
+}
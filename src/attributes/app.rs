@@ -1,14 +1,25 @@
 use nom::{
-    number::complete::{le_u8},
-    error::{ParseError},
+    number::streaming::{le_u8},
     IResult,
 };
 
+use core::hash::{Hash, Hasher};
+
+use crate::decode::Decode;
+use crate::Error;
+
 
 /// The `AppAttributeID` type. See [the module level documentation](index.html) for more.
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+#[repr(u8)]
 pub enum AppAttributeID {
     DisplayName = 0,
+    /// An ID that isn't defined by the ANCS specification, kept so it can be passed on unchanged.
+    ///
+    /// `From<u8>` only returns it for undefined IDs, one holding a defined ID anyway
+    /// compares equal to and serializes as the variant for that ID.
+    Unknown(u8),
 }
 
 impl From<AppAttributeID> for u8 {
@@ -26,31 +37,52 @@ impl From<AppAttributeID> for u8 {
     fn from(original: AppAttributeID) -> u8 {
         match original {
             AppAttributeID::DisplayName => 0,
+            AppAttributeID::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for AppAttributeID {
-    type Error = AttributeIDError;
-
-    /// Attempts to convert a `u8` to a valid `AppAttributeID`
-    /// 
+impl From<u8> for AppAttributeID {
+    /// Converts a `u8` to a `AppAttributeID`, IDs that aren't defined by ANCS become `AppAttributeID::Unknown`
+    ///
     /// # Examples
     /// ```
     /// # use ancs::attributes::app::AppAttributeID;
-    /// let app_attribute_id: AppAttributeID = AppAttributeID::try_from(0).unwrap();
-    /// 
-    /// assert_eq!(AppAttributeID::DisplayName, app_attribute_id);
+    /// assert_eq!(AppAttributeID::from(0), AppAttributeID::DisplayName);
+    /// assert_eq!(AppAttributeID::from(200), AppAttributeID::Unknown(200));
+    /// assert_eq!(u8::from(AppAttributeID::Unknown(200)), 200);
     /// ```
-    /// 
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
+    fn from(original: u8) -> AppAttributeID {
         match original {
-            0 => Ok(AppAttributeID::DisplayName),
-            _ => Err(AttributeIDError)
+            0 => AppAttributeID::DisplayName,
+            _ => AppAttributeID::Unknown(original),
         }
     }
 }
 
+impl PartialEq for AppAttributeID {
+    /// Compares two IDs by their binary representation, so an `Unknown` holding a defined
+    /// ID equals the variant for that ID
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::app::AppAttributeID;
+    /// assert_eq!(AppAttributeID::Unknown(0), AppAttributeID::DisplayName);
+    /// assert_ne!(AppAttributeID::Unknown(200), AppAttributeID::Unknown(201));
+    /// ```
+    fn eq(&self, other: &AppAttributeID) -> bool {
+        u8::from(*self) == u8::from(*other)
+    }
+}
+
+impl Eq for AppAttributeID {}
+
+impl Hash for AppAttributeID {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+    }
+}
+
 impl AppAttributeID {
     /// Attempts to parse a `AppAttributeID` from a `&[u8]`
     /// 
@@ -63,15 +95,38 @@ impl AppAttributeID {
     /// assert_eq!(AppAttributeID::DisplayName, app_attribute_id);
     /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], AppAttributeID> {
-        let (i, app_attribute_id) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], AppAttributeID, Error> {
+        AppAttributeID::decode(i)
+    }
+
+    /// Attempts to parse a `AppAttributeID` from a `&[u8]`, rejecting IDs that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::app::AppAttributeID;
+    /// assert_eq!(AppAttributeID::parse_strict(&[0]), Ok((&[][..], AppAttributeID::DisplayName)));
+    /// assert_eq!(AppAttributeID::parse_strict(&[200]), Err(nom::Err::Failure(Error::UnknownAppAttributeID(200))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], AppAttributeID, Error> {
+        let (i, app_attribute_id) = AppAttributeID::parse(i)?;
+
+        match app_attribute_id {
+            AppAttributeID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownAppAttributeID(id))),
+            app_attribute_id => Ok((i, app_attribute_id)),
+        }
+    }
 
-        match AppAttributeID::try_from(app_attribute_id) {
-            Ok(app_attribute_id) => { Ok((i, app_attribute_id)) },
-            Err(_) => Err(nom::Err::Failure(ParseError::from_error_kind(i, nom::error::ErrorKind::Fail))),
-        } 
+    /// Returns `true` if this ID isn't defined by the ANCS specification.
+    pub fn is_unknown(&self) -> bool {
+        matches!(AppAttributeID::from(u8::from(*self)), AppAttributeID::Unknown(_))
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct AttributeIDError;
\ No newline at end of file
+impl<'a> Decode<'a> for AppAttributeID {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], AppAttributeID, Error> {
+        let (i, app_attribute_id) = le_u8(i)?;
+
+        Ok((i, AppAttributeID::from(app_attribute_id)))
+    }
+}
@@ -1,9 +1,16 @@
 use nom::{
-    number::complete::{le_u8},
-    IResult, error::ParseError,
+    number::streaming::{le_u8},
+    IResult,
 };
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use core::hash::{Hash, Hasher};
+
+use crate::decode::Decode;
+use crate::Error;
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+#[repr(u8)]
 pub enum CategoryID {
     Other = 0,
     IncomingCall = 1,
@@ -17,6 +24,11 @@ pub enum CategoryID {
     BusinessAndFinance = 9,
     Location = 10,
     Entertainment = 11,
+    /// An ID that isn't defined by the ANCS specification, kept so it can be passed on unchanged.
+    ///
+    /// `From<u8>` only returns it for undefined IDs, one holding a defined ID anyway
+    /// compares equal to and serializes as the variant for that ID.
+    Unknown(u8),
 }
 
 impl From<CategoryID> for u8 {
@@ -45,43 +57,74 @@ impl From<CategoryID> for u8 {
             CategoryID::BusinessAndFinance => 9,
             CategoryID::Location => 10,
             CategoryID::Entertainment => 11,
+            CategoryID::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for CategoryID {
-    type Error = CategoryIDError;
-
-    /// Attempts to convert a u8 to a valid `CategoryID`
-    /// 
+impl From<u8> for CategoryID {
+    /// Converts a `u8` to a `CategoryID`, IDs that aren't defined by ANCS become `CategoryID::Unknown`
+    ///
     /// # Examples
-    /// 
     /// ```
-    /// # use ancs::attributes::app::AppAttributeID;
-    /// let attribute: AppAttributeID = AppAttributeID::try_from(0).unwrap();
-    /// 
-    /// assert_eq!(AppAttributeID::DisplayName, attribute);
+    /// # use ancs::attributes::category::CategoryID;
+    /// assert_eq!(CategoryID::from(0), CategoryID::Other);
+    /// assert_eq!(CategoryID::from(200), CategoryID::Unknown(200));
+    /// assert_eq!(u8::from(CategoryID::Unknown(200)), 200);
     /// ```
-    /// 
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
+    fn from(original: u8) -> CategoryID {
         match original {
-            0 => Ok(CategoryID::Other),
-            1 => Ok(CategoryID::IncomingCall),
-            2 => Ok(CategoryID::MissedCall),
-            3 => Ok(CategoryID::Voicemail),
-            4 => Ok(CategoryID::Social),
-            5 => Ok(CategoryID::Schedule),
-            6 => Ok(CategoryID::Email),
-            7 => Ok(CategoryID::News),
-            8 => Ok(CategoryID::HealthAndFitness),
-            9 => Ok(CategoryID::BusinessAndFinance),
-            10 => Ok(CategoryID::Location),
-            11 => Ok(CategoryID::Entertainment),
-            _ => Err(CategoryIDError),
+            0 => CategoryID::Other,
+            1 => CategoryID::IncomingCall,
+            2 => CategoryID::MissedCall,
+            3 => CategoryID::Voicemail,
+            4 => CategoryID::Social,
+            5 => CategoryID::Schedule,
+            6 => CategoryID::Email,
+            7 => CategoryID::News,
+            8 => CategoryID::HealthAndFitness,
+            9 => CategoryID::BusinessAndFinance,
+            10 => CategoryID::Location,
+            11 => CategoryID::Entertainment,
+            _ => CategoryID::Unknown(original),
         }
     }
 }
 
+impl PartialEq for CategoryID {
+    /// Compares two IDs by their binary representation, so an `Unknown` holding a defined
+    /// ID equals the variant for that ID
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::category::CategoryID;
+    /// assert_eq!(CategoryID::Unknown(3), CategoryID::Voicemail);
+    /// assert_ne!(CategoryID::Unknown(200), CategoryID::Unknown(201));
+    /// ```
+    fn eq(&self, other: &CategoryID) -> bool {
+        u8::from(*self) == u8::from(*other)
+    }
+}
+
+impl Eq for CategoryID {}
+
+impl Hash for CategoryID {
+    /// Hashes an ID by its binary representation, agreeing with how `PartialEq` compares it
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use ancs::attributes::category::CategoryID;
+    /// let categories = HashSet::from([CategoryID::Unknown(1), CategoryID::Other]);
+    ///
+    /// assert!(categories.contains(&CategoryID::IncomingCall));
+    /// assert_eq!(categories.len(), 2);
+    /// ```
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+    }
+}
+
 impl CategoryID {
     /// Attempts to parse a `CategoryID` from a `&[u8]`
     /// 
@@ -94,15 +137,38 @@ impl CategoryID {
     /// assert_eq!(CategoryID::Other, category_id);
     /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], CategoryID> {
-        let (i, category_id) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], CategoryID, Error> {
+        CategoryID::decode(i)
+    }
+
+    /// Attempts to parse a `CategoryID` from a `&[u8]`, rejecting IDs that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::category::CategoryID;
+    /// assert_eq!(CategoryID::parse_strict(&[0]), Ok((&[][..], CategoryID::Other)));
+    /// assert_eq!(CategoryID::parse_strict(&[200]), Err(nom::Err::Failure(Error::UnknownCategoryID(200))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], CategoryID, Error> {
+        let (i, category_id) = CategoryID::parse(i)?;
 
-        match CategoryID::try_from(category_id) {
-            Ok(category_id) => { Ok((i, category_id)) },
-            Err(_) => Err(nom::Err::Failure(ParseError::from_error_kind(i, nom::error::ErrorKind::Fail))),
+        match category_id {
+            CategoryID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownCategoryID(id))),
+            category_id => Ok((i, category_id)),
         }
     }
+
+    /// Returns `true` if this ID isn't defined by the ANCS specification.
+    pub fn is_unknown(&self) -> bool {
+        matches!(CategoryID::from(u8::from(*self)), CategoryID::Unknown(_))
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct CategoryIDError;
\ No newline at end of file
+impl<'a> Decode<'a> for CategoryID {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], CategoryID, Error> {
+        let (i, category_id) = le_u8(i)?;
+
+        Ok((i, CategoryID::from(category_id)))
+    }
+}
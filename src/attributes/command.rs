@@ -1,13 +1,25 @@
 use nom::{
-    number::complete::{le_u8},
-    IResult, error::ParseError,
+    number::streaming::{le_u8},
+    IResult,
 };
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use core::hash::{Hash, Hasher};
+
+use crate::decode::Decode;
+use crate::Error;
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+#[repr(u8)]
 pub enum CommandID {
     GetNotificationAttributes = 0,
     GetAppAttributes = 1,
     PerformNotificationAction = 2,
+    /// An ID that isn't defined by the ANCS specification, kept so it can be passed on unchanged.
+    ///
+    /// `From<u8>` only returns it for undefined IDs, one holding a defined ID anyway
+    /// compares equal to and serializes as the variant for that ID.
+    Unknown(u8),
 }
 
 impl From<CommandID> for u8 {
@@ -25,31 +37,54 @@ impl From<CommandID> for u8 {
             CommandID::GetNotificationAttributes => 0,
             CommandID::GetAppAttributes => 1,
             CommandID::PerformNotificationAction => 2,
+            CommandID::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for CommandID {
-    type Error = CommandIDError;
-    /// Attempts to convert a `u8` to a valid `CommandID`
-    /// 
+impl From<u8> for CommandID {
+    /// Converts a `u8` to a `CommandID`, IDs that aren't defined by ANCS become `CommandID::Unknown`
+    ///
     /// # Examples
     /// ```
     /// # use ancs::attributes::command::CommandID;
-    /// let command: CommandID = CommandID::try_from(0).unwrap();
-    /// 
-    /// assert_eq!(CommandID::GetNotificationAttributes, command);
+    /// assert_eq!(CommandID::from(0), CommandID::GetNotificationAttributes);
+    /// assert_eq!(CommandID::from(200), CommandID::Unknown(200));
+    /// assert_eq!(u8::from(CommandID::Unknown(200)), 200);
     /// ```
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
+    fn from(original: u8) -> CommandID {
         match original {
-            0 => Ok(CommandID::GetNotificationAttributes),
-            1 => Ok(CommandID::GetAppAttributes),
-            2 => Ok(CommandID::PerformNotificationAction),
-            _ => Err(CommandIDError),
+            0 => CommandID::GetNotificationAttributes,
+            1 => CommandID::GetAppAttributes,
+            2 => CommandID::PerformNotificationAction,
+            _ => CommandID::Unknown(original),
         }
     }
 }
 
+impl PartialEq for CommandID {
+    /// Compares two IDs by their binary representation, so an `Unknown` holding a defined
+    /// ID equals the variant for that ID
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::command::CommandID;
+    /// assert_eq!(CommandID::Unknown(2), CommandID::PerformNotificationAction);
+    /// assert_ne!(CommandID::Unknown(200), CommandID::Unknown(201));
+    /// ```
+    fn eq(&self, other: &CommandID) -> bool {
+        u8::from(*self) == u8::from(*other)
+    }
+}
+
+impl Eq for CommandID {}
+
+impl Hash for CommandID {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+    }
+}
+
 impl CommandID {
     /// Attempts to parse a `CommandID` from a `&[u8]`
     /// 
@@ -61,15 +96,48 @@ impl CommandID {
     /// 
     /// assert_eq!(CommandID::GetNotificationAttributes, command_id);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], CommandID> {
-        let (i, command_id) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], CommandID, Error> {
+        CommandID::decode(i)
+    }
+
+    /// Attempts to parse a `CommandID` from a `&[u8]`, rejecting IDs that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::command::CommandID;
+    /// assert_eq!(CommandID::parse_strict(&[0]), Ok((&[][..], CommandID::GetNotificationAttributes)));
+    /// assert_eq!(CommandID::parse_strict(&[200]), Err(nom::Err::Failure(Error::UnknownCommandID(200))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], CommandID, Error> {
+        let (i, command_id) = CommandID::parse(i)?;
+
+        match command_id {
+            CommandID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownCommandID(id))),
+            command_id => Ok((i, command_id)),
+        }
+    }
+
+    /// Parses a `CommandID`, failing with `Error::CommandIDMismatch` unless it is `expected`.
+    pub(crate) fn parse_expected(i: &[u8], expected: CommandID) -> IResult<&[u8], CommandID, Error> {
+        let (i, actual) = CommandID::decode_streaming(i)?;
 
-        match CommandID::try_from(command_id) {
-            Ok(command_id) => { Ok((i, command_id)) },
-            Err(_) => { Err(nom::Err::Failure(ParseError::from_error_kind(i, nom::error::ErrorKind::Fail))) },
+        match actual == expected {
+            true => Ok((i, actual)),
+            false => Err(nom::Err::Failure(Error::CommandIDMismatch { expected, actual })),
         }
     }
+
+    /// Returns `true` if this ID isn't defined by the ANCS specification.
+    pub fn is_unknown(&self) -> bool {
+        matches!(CommandID::from(u8::from(*self)), CommandID::Unknown(_))
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct CommandIDError;
\ No newline at end of file
+impl<'a> Decode<'a> for CommandID {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], CommandID, Error> {
+        let (i, command_id) = le_u8(i)?;
+
+        Ok((i, CommandID::from(command_id)))
+    }
+}
@@ -0,0 +1,274 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::Error;
+
+/// The local date and time carried by the `NotificationAttributeID::Date` attribute.
+///
+/// ANCS formats dates as `yyyyMMdd'T'HHmmSS` using the iOS device's local time zone
+/// and doesn't include a UTC offset.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// Attempts to create a `DateTime`, returning `Error::InvalidDate` if any field is out of range
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::date::DateTime;
+    /// assert!(DateTime::new(2024, 2, 29, 13, 5, 0).is_ok());
+    /// assert_eq!(DateTime::new(2023, 2, 29, 13, 5, 0), Err(Error::InvalidDate));
+    /// ```
+    // `u16::is_multiple_of` is only stable since Rust 1.87.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<DateTime, Error> {
+        let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap_year => 29,
+            2 => 28,
+            _ => return Err(Error::InvalidDate),
+        };
+
+        if year > 9999 || day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 59 {
+            return Err(Error::InvalidDate);
+        }
+
+        Ok(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Attaches a UTC offset, such as the one reported by the Current Time Service,
+    /// to get the absolute instant the date refers to
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// # use chrono::{FixedOffset, TimeZone, Utc};
+    /// let date = DateTime::new(2024, 2, 29, 13, 5, 0).unwrap();
+    /// let instant = date.with_chrono_offset(FixedOffset::east_opt(2 * 3600).unwrap());
+    ///
+    /// assert_eq!(instant, Utc.with_ymd_and_hms(2024, 2, 29, 11, 5, 0).unwrap());
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn with_chrono_offset(self, offset: chrono::FixedOffset) -> chrono::DateTime<chrono::FixedOffset> {
+        chrono::NaiveDateTime::from(self)
+            .and_local_timezone(offset)
+            .single()
+            .expect("a fixed offset maps every local time to exactly one instant")
+    }
+
+    /// Attaches a UTC offset, such as the one reported by the Current Time Service,
+    /// to get the absolute instant the date refers to
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// # use time::{macros::datetime, UtcOffset};
+    /// let date = DateTime::new(2024, 2, 29, 13, 5, 0).unwrap();
+    /// let instant = date.with_time_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    ///
+    /// assert_eq!(instant, datetime!(2024-02-29 11:05:00 UTC));
+    /// ```
+    #[cfg(feature = "time")]
+    pub fn with_time_offset(self, offset: time::UtcOffset) -> time::OffsetDateTime {
+        time::PrimitiveDateTime::from(self).assume_offset(offset)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime> for chrono::NaiveDateTime {
+    /// Converts a `DateTime` to a `chrono::NaiveDateTime`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// # use chrono::NaiveDate;
+    /// let date = DateTime::new(2024, 2, 29, 13, 5, 0).unwrap();
+    /// let expected = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_opt(13, 5, 0).unwrap();
+    ///
+    /// assert_eq!(chrono::NaiveDateTime::from(date), expected);
+    /// ```
+    fn from(original: DateTime) -> chrono::NaiveDateTime {
+        // Every field was range checked when the `DateTime` was created and chrono
+        // supports every year ANCS can represent.
+        chrono::NaiveDate::from_ymd_opt(original.year.into(), original.month.into(), original.day.into())
+            .and_then(|date| date.and_hms_opt(original.hour.into(), original.minute.into(), original.second.into()))
+            .expect("DateTime is always a valid date")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::NaiveDateTime> for DateTime {
+    type Error = Error;
+
+    /// Attempts to convert a `chrono::NaiveDateTime` to a `DateTime`, sub-second precision is dropped
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// # use chrono::NaiveDate;
+    /// let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_opt(13, 5, 0).unwrap();
+    ///
+    /// assert_eq!(DateTime::try_from(date).unwrap().to_string(), "20240229T130500");
+    /// ```
+    fn try_from(original: chrono::NaiveDateTime) -> Result<DateTime, Error> {
+        use chrono::{Datelike, Timelike};
+
+        DateTime::new(
+            u16::try_from(original.year()).map_err(|_| Error::InvalidDate)?,
+            original.month() as u8,
+            original.day() as u8,
+            original.hour() as u8,
+            original.minute() as u8,
+            // chrono represents leap seconds with an oversized nanosecond field, not a 60th second.
+            original.second() as u8,
+        )
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<DateTime> for time::PrimitiveDateTime {
+    /// Converts a `DateTime` to a `time::PrimitiveDateTime`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// # use time::macros::datetime;
+    /// let date = DateTime::new(2024, 2, 29, 13, 5, 0).unwrap();
+    ///
+    /// assert_eq!(time::PrimitiveDateTime::from(date), datetime!(2024-02-29 13:05:00));
+    /// ```
+    fn from(original: DateTime) -> time::PrimitiveDateTime {
+        // Every field was range checked when the `DateTime` was created.
+        let month = time::Month::try_from(original.month).expect("DateTime is always a valid date");
+        let date = time::Date::from_calendar_date(original.year.into(), month, original.day)
+            .expect("DateTime is always a valid date");
+        let time = time::Time::from_hms(original.hour, original.minute, original.second)
+            .expect("DateTime is always a valid time");
+
+        time::PrimitiveDateTime::new(date, time)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::PrimitiveDateTime> for DateTime {
+    type Error = Error;
+
+    /// Attempts to convert a `time::PrimitiveDateTime` to a `DateTime`, sub-second precision is dropped
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// # use time::macros::datetime;
+    /// let date = DateTime::try_from(datetime!(2024-02-29 13:05:00)).unwrap();
+    ///
+    /// assert_eq!(date.to_string(), "20240229T130500");
+    /// ```
+    fn try_from(original: time::PrimitiveDateTime) -> Result<DateTime, Error> {
+        DateTime::new(
+            u16::try_from(original.year()).map_err(|_| Error::InvalidDate)?,
+            original.month().into(),
+            original.day(),
+            original.hour(),
+            original.minute(),
+            original.second(),
+        )
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = Error;
+
+    /// Attempts to parse a `DateTime` from its ANCS `yyyyMMdd'T'HHmmSS` representation
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// let date: DateTime = "20240229T130500".parse().unwrap();
+    ///
+    /// assert_eq!(date, DateTime::new(2024, 2, 29, 13, 5, 0).unwrap());
+    /// assert!("2024-02-29 13:05:00".parse::<DateTime>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<DateTime, Error> {
+        let bytes = s.as_bytes();
+
+        if bytes.len() != 15 || bytes[8] != b'T' {
+            return Err(Error::InvalidDate);
+        }
+
+        let number = |range: core::ops::Range<usize>| -> Result<u16, Error> {
+            bytes[range].iter().try_fold(0_u16, |value, &b| match b {
+                b'0'..=b'9' => Ok(value * 10 + u16::from(b - b'0')),
+                _ => Err(Error::InvalidDate),
+            })
+        };
+
+        DateTime::new(
+            number(0..4)?,
+            number(4..6)? as u8,
+            number(6..8)? as u8,
+            number(9..11)? as u8,
+            number(11..13)? as u8,
+            number(13..15)? as u8,
+        )
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Formats a `DateTime` as `yyyyMMdd'T'HHmmSS`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// let date = DateTime::new(2024, 2, 29, 13, 5, 0).unwrap();
+    ///
+    /// assert_eq!(date.to_string(), "20240229T130500");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}{:02}{:02}T{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
@@ -1,13 +1,25 @@
 use nom::{
-    number::complete::{le_u8},
-    IResult, error::ParseError,
+    number::streaming::{le_u8},
+    IResult,
 };
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use crate::decode::Decode;
+use crate::Error;
+use core::hash::{Hash, Hasher};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+#[repr(u8)]
 pub enum EventID {
     NotificationAdded = 0,
     NotificationModified = 1,
     NotificationRemoved = 2,
+    /// An ID that isn't defined by the ANCS specification, kept so it can be passed on unchanged.
+    ///
+    /// `From<u8>` only returns it for undefined IDs, one holding a defined ID anyway
+    /// compares equal to and serializes as the variant for that ID.
+    Unknown(u8),
 }
 
 impl From<EventID> for u8 {
@@ -25,31 +37,54 @@ impl From<EventID> for u8 {
             EventID::NotificationAdded => 0,
             EventID::NotificationModified => 1,
             EventID::NotificationRemoved => 2,
+            EventID::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for EventID {
-    type Error = EventIDError;
-    /// Attempts to convert a `u8` to a `EventID`
-    /// 
+impl From<u8> for EventID {
+    /// Converts a `u8` to a `EventID`, IDs that aren't defined by ANCS become `EventID::Unknown`
+    ///
     /// # Examples
     /// ```
     /// # use ancs::attributes::event::EventID;
-    /// let event_id: EventID = EventID::try_from(0).unwrap();
-    /// 
-    /// assert_eq!(EventID::NotificationAdded, event_id);
+    /// assert_eq!(EventID::from(0), EventID::NotificationAdded);
+    /// assert_eq!(EventID::from(200), EventID::Unknown(200));
+    /// assert_eq!(u8::from(EventID::Unknown(200)), 200);
     /// ```
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
+    fn from(original: u8) -> EventID {
         match original {
-            0 => Ok(EventID::NotificationAdded),
-            1 => Ok(EventID::NotificationModified),
-            2 => Ok(EventID::NotificationRemoved),
-            _ => Err(EventIDError),
+            0 => EventID::NotificationAdded,
+            1 => EventID::NotificationModified,
+            2 => EventID::NotificationRemoved,
+            _ => EventID::Unknown(original),
         }
     }
 }
 
+impl PartialEq for EventID {
+    /// Compares two IDs by their binary representation, so an `Unknown` holding a defined
+    /// ID equals the variant for that ID
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::event::EventID;
+    /// assert_eq!(EventID::Unknown(2), EventID::NotificationRemoved);
+    /// assert_ne!(EventID::Unknown(200), EventID::Unknown(201));
+    /// ```
+    fn eq(&self, other: &EventID) -> bool {
+        u8::from(*self) == u8::from(*other)
+    }
+}
+
+impl Eq for EventID {}
+
+impl Hash for EventID {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+    }
+}
+
 impl EventID {
     /// Attempts to parse a `EventID` from a `&[u8]`
     /// 
@@ -62,20 +97,48 @@ impl EventID {
     /// assert_eq!(EventID::NotificationAdded, event_id);
     /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], EventID> {
-        let (i, event_id) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], EventID, Error> {
+        EventID::decode(i)
+    }
+
+    /// Attempts to parse a `EventID` from a `&[u8]`, rejecting IDs that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::event::EventID;
+    /// assert_eq!(EventID::parse_strict(&[0]), Ok((&[][..], EventID::NotificationAdded)));
+    /// assert_eq!(EventID::parse_strict(&[200]), Err(nom::Err::Failure(Error::UnknownEventID(200))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], EventID, Error> {
+        let (i, event_id) = EventID::parse(i)?;
 
-        match EventID::try_from(event_id) {
-            Ok(event_id) => { Ok((i, event_id)) },
-            Err(_) => Err(nom::Err::Failure(ParseError::from_error_kind(i, nom::error::ErrorKind::Fail))),
+        match event_id {
+            EventID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownEventID(id))),
+            event_id => Ok((i, event_id)),
         }
     }
+
+    /// Returns `true` if this ID isn't defined by the ANCS specification.
+    pub fn is_unknown(&self) -> bool {
+        matches!(EventID::from(u8::from(*self)), EventID::Unknown(_))
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct EventIDError;
+impl<'a> Decode<'a> for EventID {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], EventID, Error> {
+        let (i, event_id) = le_u8(i)?;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+        Ok((i, EventID::from(event_id)))
+    }
+}
+
+/// A single flag that may be set in a notification's `EventFlags`.
+///
+/// The flags byte is always read as a whole into `EventFlags`, use `EventFlags::iter` to
+/// get the individual flags set in it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EventFlag {
     Silent = 0b00000001,
     Important = 0b00000010,
@@ -105,51 +168,231 @@ impl From<EventFlag> for u8 {
     }
 }
 
-impl TryFrom<u8> for EventFlag {
-    type Error = EventFlagError;
+impl BitOr for EventFlag {
+    type Output = EventFlags;
 
-    /// Attempts to convert a `u8` to a `EventFlag`
-    /// 
+    /// Combines two `EventFlag`s into a set of `EventFlags`
+    ///
     /// # Examples
     /// ```
-    /// # use ancs::attributes::event::EventFlag;
-    /// let event_flag: EventFlag = EventFlag::try_from(0b00000001).unwrap();
-    /// 
-    /// assert_eq!(EventFlag::Silent, event_flag);
-    /// ```
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
-        match original.trailing_zeros() {
-            0 => Ok(EventFlag::Silent),
-            1 => Ok(EventFlag::Important),
-            2 => Ok(EventFlag::PreExisting),
-            3 => Ok(EventFlag::PositiveAction),
-            4 => Ok(EventFlag::NegativeAction),
-            _ => Err(EventFlagError),
-        }
+    /// # use ancs::attributes::event::{EventFlag, EventFlags};
+    /// let flags: EventFlags = EventFlag::PositiveAction | EventFlag::NegativeAction;
+    ///
+    /// assert_eq!(0b00011000, u8::from(flags));
+    /// ```
+    fn bitor(self, rhs: EventFlag) -> EventFlags {
+        EventFlags::from(self) | rhs
     }
 }
 
-impl EventFlag {
-    /// Attempts to parse a `EventFlag` from a `&[u8]`
+const EVENT_FLAGS: [EventFlag; 5] = [
+    EventFlag::Silent,
+    EventFlag::Important,
+    EventFlag::PreExisting,
+    EventFlag::PositiveAction,
+    EventFlag::NegativeAction,
+];
+
+/// The set of `EventFlag`s attached to a notification.
+///
+/// Every bit of the original byte is kept, including the bits reserved by the
+/// ANCS specification, so a parsed set always encodes back to the same byte.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventFlags(u8);
+
+impl EventFlags {
+    /// Returns a set with no flags in it.
+    pub const fn empty() -> EventFlags {
+        EventFlags(0)
+    }
+
+    /// Creates a set from its raw bits, keeping any reserved bits as-is.
+    pub const fn from_bits(bits: u8) -> EventFlags {
+        EventFlags(bits)
+    }
+
+    /// Returns the raw bits of the set.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns only the bits not assigned to any `EventFlag`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::event::EventFlags;
+    /// let flags = EventFlags::from_bits(0b10000010);
+    ///
+    /// assert_eq!(0b10000000, flags.reserved_bits());
+    /// ```
+    pub fn reserved_bits(self) -> u8 {
+        self.0 & !EVENT_FLAGS.iter().fold(0, |bits, &flag| bits | u8::from(flag))
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Determines if every flag in `other` is also in this set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::event::{EventFlag, EventFlags};
+    /// let flags = EventFlags::from_bits(0b00011010);
+    ///
+    /// assert!(flags.contains(EventFlag::Important));
+    /// assert!(flags.contains(EventFlag::PositiveAction | EventFlag::NegativeAction));
+    /// assert!(!flags.contains(EventFlag::Silent));
+    /// ```
+    pub fn contains<F: Into<EventFlags>>(self, other: F) -> bool {
+        let other = other.into();
+
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds every flag in `other` to this set.
+    pub fn insert<F: Into<EventFlags>>(&mut self, other: F) {
+        self.0 |= other.into().0;
+    }
+
+    /// Removes every flag in `other` from this set.
+    pub fn remove<F: Into<EventFlags>>(&mut self, other: F) {
+        self.0 &= !other.into().0;
+    }
+
+    /// Returns the flags found in either set.
+    pub fn union(self, other: EventFlags) -> EventFlags {
+        EventFlags(self.0 | other.0)
+    }
+
+    /// Returns the flags found in both sets.
+    pub fn intersection(self, other: EventFlags) -> EventFlags {
+        EventFlags(self.0 & other.0)
+    }
+
+    /// Returns the flags found in this set but not in `other`.
+    pub fn difference(self, other: EventFlags) -> EventFlags {
+        EventFlags(self.0 & !other.0)
+    }
+
+    /// Returns the flags found in exactly one of the two sets.
+    pub fn symmetric_difference(self, other: EventFlags) -> EventFlags {
+        EventFlags(self.0 ^ other.0)
+    }
+
+    /// Iterates over the known `EventFlag`s in this set, reserved bits are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::event::{EventFlag, EventFlags};
+    /// let flags = EventFlags::from_bits(0b10011010);
+    /// let flags: Vec<EventFlag> = flags.iter().collect();
+    ///
+    /// assert_eq!(flags, vec![EventFlag::Important, EventFlag::PositiveAction, EventFlag::NegativeAction]);
+    /// ```
+    pub fn iter(self) -> impl Iterator<Item = EventFlag> {
+        EVENT_FLAGS.into_iter().filter(move |&flag| self.contains(flag))
+    }
+
+    /// Attempts to parse `EventFlags` from a `&[u8]`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::event::EventFlag;
-    /// let data: [u8; 2] = [0b00000001, 0b00000010];
-    /// let (data, event_flag) = EventFlag::parse(&data).unwrap();
+    /// # use ancs::attributes::event::{EventFlag, EventFlags};
+    /// let data: [u8; 2] = [0b00011010, 0b00000010];
+    /// let (data, event_flags) = EventFlags::parse(&data).unwrap();
     /// 
-    /// assert_eq!(EventFlag::Silent, event_flag);
+    /// assert_eq!(EventFlag::Important | EventFlag::PositiveAction | EventFlag::NegativeAction, event_flags);
     /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], EventFlag> {
-        let (i, event_flag) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], EventFlags, Error> {
+        EventFlags::decode(i)
+    }
+}
 
-        match EventFlag::try_from(event_flag) {
-            Ok(event_flag) => { Ok((i, event_flag)) },
-            Err(_) => Err(nom::Err::Failure(ParseError::from_error_kind(i, nom::error::ErrorKind::Fail))),
-        }
+impl<'a> Decode<'a> for EventFlags {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], EventFlags, Error> {
+        let (i, event_flags) = le_u8(i)?;
+
+        Ok((i, EventFlags(event_flags)))
+    }
+}
+
+impl From<EventFlag> for EventFlags {
+    fn from(original: EventFlag) -> EventFlags {
+        EventFlags(original.into())
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct EventFlagError;
+impl From<u8> for EventFlags {
+    fn from(original: u8) -> EventFlags {
+        EventFlags(original)
+    }
+}
+
+impl From<EventFlags> for u8 {
+    fn from(original: EventFlags) -> u8 {
+        original.0
+    }
+}
+
+impl FromIterator<EventFlag> for EventFlags {
+    fn from_iter<T: IntoIterator<Item = EventFlag>>(iter: T) -> EventFlags {
+        let mut flags = EventFlags::empty();
+
+        iter.into_iter().for_each(|flag| flags.insert(flag));
+
+        flags
+    }
+}
+
+impl<F: Into<EventFlags>> BitOr<F> for EventFlags {
+    type Output = EventFlags;
+
+    fn bitor(self, rhs: F) -> EventFlags {
+        self.union(rhs.into())
+    }
+}
+
+impl<F: Into<EventFlags>> BitOrAssign<F> for EventFlags {
+    fn bitor_assign(&mut self, rhs: F) {
+        self.insert(rhs);
+    }
+}
+
+impl<F: Into<EventFlags>> BitAnd<F> for EventFlags {
+    type Output = EventFlags;
+
+    fn bitand(self, rhs: F) -> EventFlags {
+        self.intersection(rhs.into())
+    }
+}
+
+impl<F: Into<EventFlags>> BitAndAssign<F> for EventFlags {
+    fn bitand_assign(&mut self, rhs: F) {
+        *self = self.intersection(rhs.into());
+    }
+}
+
+impl<F: Into<EventFlags>> BitXor<F> for EventFlags {
+    type Output = EventFlags;
+
+    fn bitxor(self, rhs: F) -> EventFlags {
+        self.symmetric_difference(rhs.into())
+    }
+}
+
+impl<F: Into<EventFlags>> Sub<F> for EventFlags {
+    type Output = EventFlags;
+
+    fn sub(self, rhs: F) -> EventFlags {
+        self.difference(rhs.into())
+    }
+}
+
+impl<F: Into<EventFlags>> SubAssign<F> for EventFlags {
+    fn sub_assign(&mut self, rhs: F) {
+        self.remove(rhs);
+    }
+}
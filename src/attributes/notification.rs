@@ -1,11 +1,19 @@
 use nom::{
-    number::complete::{le_u8},
-    IResult, error::ParseError,
+    number::streaming::{le_u8, le_u16},
+    IResult,
 };
 
+use core::hash::{Hash, Hasher};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, Writer};
+use crate::Error;
+
 /// Provides a set of identifiers for types of attributes that a consumer may require.
 /// This list of `NotificationAttributeID`s follows the ANCS Specification for valid NotificationAttributeIDs
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+#[repr(u8)]
 pub enum NotificationAttributeID {
     AppIdentifier = 0,
     Title = 1,
@@ -15,6 +23,11 @@ pub enum NotificationAttributeID {
     Date = 5,
     PositiveActionLabel = 6,
     NegativeActionLabel = 7,
+    /// An ID that isn't defined by the ANCS specification, kept so it can be passed on unchanged.
+    ///
+    /// `From<u8>` only returns it for undefined IDs, one holding a defined ID anyway
+    /// compares equal to and serializes as the variant for that ID.
+    Unknown(u8),
 }
 
 impl From<NotificationAttributeID> for u8 {
@@ -37,38 +50,59 @@ impl From<NotificationAttributeID> for u8 {
             NotificationAttributeID::Date => 5,
             NotificationAttributeID::PositiveActionLabel => 6,
             NotificationAttributeID::NegativeActionLabel => 7,
+            NotificationAttributeID::Unknown(id) => id,
         }
     }
 }
 
-impl TryFrom<u8> for NotificationAttributeID {
-    type Error = NotificationAttributeIDError;
-
-    /// Attempts to convert a `u8` to a `NotificationAttributeID`
-    /// 
+impl From<u8> for NotificationAttributeID {
+    /// Converts a `u8` to a `NotificationAttributeID`, IDs that aren't defined by ANCS become `NotificationAttributeID::Unknown`
+    ///
     /// # Examples
     /// ```
     /// # use ancs::attributes::notification::NotificationAttributeID;
-    /// let attribute: NotificationAttributeID = NotificationAttributeID::try_from(0).unwrap();
-    /// 
-    /// assert_eq!(NotificationAttributeID::AppIdentifier, attribute);
+    /// assert_eq!(NotificationAttributeID::from(0), NotificationAttributeID::AppIdentifier);
+    /// assert_eq!(NotificationAttributeID::from(200), NotificationAttributeID::Unknown(200));
+    /// assert_eq!(u8::from(NotificationAttributeID::Unknown(200)), 200);
     /// ```
-    /// 
-    fn try_from(original: u8) -> Result<Self, Self::Error> {
+    fn from(original: u8) -> NotificationAttributeID {
         match original {
-            0 => Ok(NotificationAttributeID::AppIdentifier),
-            1 => Ok(NotificationAttributeID::Title),
-            2 => Ok(NotificationAttributeID::Subtitle),
-            3 => Ok(NotificationAttributeID::Message),
-            4 => Ok(NotificationAttributeID::MessageSize),
-            5 => Ok(NotificationAttributeID::Date),
-            6 => Ok(NotificationAttributeID::PositiveActionLabel),
-            7 => Ok(NotificationAttributeID::NegativeActionLabel),
-            _ => Err(NotificationAttributeIDError),
+            0 => NotificationAttributeID::AppIdentifier,
+            1 => NotificationAttributeID::Title,
+            2 => NotificationAttributeID::Subtitle,
+            3 => NotificationAttributeID::Message,
+            4 => NotificationAttributeID::MessageSize,
+            5 => NotificationAttributeID::Date,
+            6 => NotificationAttributeID::PositiveActionLabel,
+            7 => NotificationAttributeID::NegativeActionLabel,
+            _ => NotificationAttributeID::Unknown(original),
         }
     }
 }
 
+impl PartialEq for NotificationAttributeID {
+    /// Compares two IDs by their binary representation, so an `Unknown` holding a defined
+    /// ID equals the variant for that ID
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// assert_eq!(NotificationAttributeID::Unknown(1), NotificationAttributeID::Title);
+    /// assert_ne!(NotificationAttributeID::Unknown(200), NotificationAttributeID::Unknown(201));
+    /// ```
+    fn eq(&self, other: &NotificationAttributeID) -> bool {
+        u8::from(*self) == u8::from(*other)
+    }
+}
+
+impl Eq for NotificationAttributeID {}
+
+impl Hash for NotificationAttributeID {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u8::from(*self).hash(state);
+    }
+}
+
 impl NotificationAttributeID {
     /// Attempts to parse a `NotificationAttributeID` from a `&[u8]`
     /// 
@@ -81,15 +115,33 @@ impl NotificationAttributeID {
     /// assert_eq!(NotificationAttributeID::AppIdentifier, notification_attribute_id);
     /// ```
     /// 
-    pub fn parse(i: &[u8]) -> IResult<&[u8], NotificationAttributeID> {
-        let (i, notification_attribute_id) = le_u8(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], NotificationAttributeID, Error> {
+        NotificationAttributeID::decode(i)
+    }
 
-        match NotificationAttributeID::try_from(notification_attribute_id) {
-            Ok(notification_attribute_id) => { Ok((i, notification_attribute_id)) },
-            Err(_) => Err(nom::Err::Failure(ParseError::from_error_kind(i, nom::error::ErrorKind::Fail))),
+    /// Attempts to parse a `NotificationAttributeID` from a `&[u8]`, rejecting IDs that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// assert_eq!(NotificationAttributeID::parse_strict(&[0]), Ok((&[][..], NotificationAttributeID::AppIdentifier)));
+    /// assert_eq!(NotificationAttributeID::parse_strict(&[200]), Err(nom::Err::Failure(Error::UnknownNotificationAttributeID(200))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], NotificationAttributeID, Error> {
+        let (i, notification_attribute_id) = NotificationAttributeID::parse(i)?;
+
+        match notification_attribute_id {
+            NotificationAttributeID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownNotificationAttributeID(id))),
+            notification_attribute_id => Ok((i, notification_attribute_id)),
         }
     }
 
+    /// Returns `true` if this ID isn't defined by the ANCS specification.
+    pub fn is_unknown(&self) -> bool {
+        matches!(NotificationAttributeID::from(u8::from(*self)), NotificationAttributeID::Unknown(_))
+    }
+
     /// Determines if a `NotificationAttributeID` has a size based
     /// on requirements defined in the ANCS specification.
     /// 
@@ -104,14 +156,187 @@ impl NotificationAttributeID {
     /// ```
     /// 
     pub fn is_sized(id: NotificationAttributeID) -> bool {
-        match id {
-            NotificationAttributeID::Title => true,
-            NotificationAttributeID::Subtitle => true,
-            NotificationAttributeID::Message => true,
-            _ => false
+        matches!(
+            NotificationAttributeID::from(u8::from(id)),
+            NotificationAttributeID::Title | NotificationAttributeID::Subtitle | NotificationAttributeID::Message
+        )
+    }
+}
+
+impl<'a> Decode<'a> for NotificationAttributeID {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], NotificationAttributeID, Error> {
+        let (i, notification_attribute_id) = le_u8(i)?;
+
+        Ok((i, NotificationAttributeID::from(notification_attribute_id)))
+    }
+}
+
+/// A `NotificationAttributeID` as requested through the Control Point.
+///
+/// ANCS requires a maximum length for the attributes that `NotificationAttributeID::is_sized`
+/// and rejects one for every other attribute, so only the sized attributes carry a length here.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(remote = "Self"))]
+pub enum RequestedAttribute {
+    AppIdentifier,
+    Title(u16),
+    Subtitle(u16),
+    Message(u16),
+    MessageSize,
+    Date,
+    PositiveActionLabel,
+    NegativeActionLabel,
+    /// An attribute that isn't defined by the ANCS specification, requested without a length.
+    ///
+    /// One holding the ID of a defined attribute without a length compares equal to and
+    /// serializes as that attribute, one holding the ID of a sized attribute can't be encoded.
+    Unknown(u8),
+}
+
+impl PartialEq for RequestedAttribute {
+    /// Compares two requested attributes by their ID and maximum length
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// assert_eq!(RequestedAttribute::Unknown(5), RequestedAttribute::Date);
+    /// assert_ne!(RequestedAttribute::Unknown(1), RequestedAttribute::Title(32));
+    /// ```
+    fn eq(&self, other: &RequestedAttribute) -> bool {
+        (u8::from(self.id()), self.max_length()) == (u8::from(other.id()), other.max_length())
+    }
+}
+
+impl Eq for RequestedAttribute {}
+
+impl Hash for RequestedAttribute {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (u8::from(self.id()), self.max_length()).hash(state);
+    }
+}
+
+impl RequestedAttribute {
+    /// Returns the `NotificationAttributeID` being requested.
+    pub fn id(&self) -> NotificationAttributeID {
+        match self {
+            RequestedAttribute::AppIdentifier => NotificationAttributeID::AppIdentifier,
+            RequestedAttribute::Title(_) => NotificationAttributeID::Title,
+            RequestedAttribute::Subtitle(_) => NotificationAttributeID::Subtitle,
+            RequestedAttribute::Message(_) => NotificationAttributeID::Message,
+            RequestedAttribute::MessageSize => NotificationAttributeID::MessageSize,
+            RequestedAttribute::Date => NotificationAttributeID::Date,
+            RequestedAttribute::PositiveActionLabel => NotificationAttributeID::PositiveActionLabel,
+            RequestedAttribute::NegativeActionLabel => NotificationAttributeID::NegativeActionLabel,
+            RequestedAttribute::Unknown(id) => NotificationAttributeID::from(*id),
+        }
+    }
+
+    /// Returns the maximum length requested for a sized attribute.
+    pub fn max_length(&self) -> Option<u16> {
+        match self {
+            RequestedAttribute::Title(length) => Some(*length),
+            RequestedAttribute::Subtitle(length) => Some(*length),
+            RequestedAttribute::Message(length) => Some(*length),
+            _ => None,
+        }
+    }
+
+    /// Attempts to parse a `RequestedAttribute` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// let data: [u8; 4] = [1, 255, 255, 5];
+    /// let (data, title) = RequestedAttribute::parse(&data).unwrap();
+    /// let (_, date) = RequestedAttribute::parse(data).unwrap();
+    ///
+    /// assert_eq!(title, RequestedAttribute::Title(u16::MAX));
+    /// assert_eq!(date, RequestedAttribute::Date);
+    ///
+    /// // Sized attributes must carry a length
+    /// assert_eq!(RequestedAttribute::parse(&[1]), Err(nom::Err::Error(Error::Truncated)));
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], RequestedAttribute, Error> {
+        RequestedAttribute::decode(i)
+    }
+}
+
+impl<'a> Decode<'a> for RequestedAttribute {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], RequestedAttribute, Error> {
+        let (i, id) = NotificationAttributeID::decode_streaming(i)?;
+
+        let (i, attribute) = match id {
+            NotificationAttributeID::Title => le_u16(i).map(|(i, length)| (i, RequestedAttribute::Title(length)))?,
+            NotificationAttributeID::Subtitle => le_u16(i).map(|(i, length)| (i, RequestedAttribute::Subtitle(length)))?,
+            NotificationAttributeID::Message => le_u16(i).map(|(i, length)| (i, RequestedAttribute::Message(length)))?,
+            NotificationAttributeID::AppIdentifier => (i, RequestedAttribute::AppIdentifier),
+            NotificationAttributeID::MessageSize => (i, RequestedAttribute::MessageSize),
+            NotificationAttributeID::Date => (i, RequestedAttribute::Date),
+            NotificationAttributeID::PositiveActionLabel => (i, RequestedAttribute::PositiveActionLabel),
+            NotificationAttributeID::NegativeActionLabel => (i, RequestedAttribute::NegativeActionLabel),
+            NotificationAttributeID::Unknown(id) => (i, RequestedAttribute::Unknown(id)),
+        };
+
+        Ok((i, attribute))
+    }
+}
+
+impl Encode for RequestedAttribute {
+    fn encoded_len(&self) -> usize {
+        match self.max_length() {
+            Some(_) => 3,
+            None => 1,
         }
     }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        if NotificationAttributeID::is_sized(self.id()) && self.max_length().is_none() {
+            return Err(Error::MaxLengthMismatch(self.id()));
+        }
+
+        let mut writer = Writer::new(buf);
+        writer.put_u8(self.id().into())?;
+
+        if let Some(length) = self.max_length() {
+            writer.put(&length.to_le_bytes())?;
+        }
+
+        Ok(writer.finish())
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct NotificationAttributeIDError;
\ No newline at end of file
+impl TryFrom<(NotificationAttributeID, Option<u16>)> for RequestedAttribute {
+    type Error = Error;
+
+    /// Attempts to pair a `NotificationAttributeID` with an optional maximum length
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::{NotificationAttributeID, RequestedAttribute};
+    /// assert_eq!(RequestedAttribute::try_from((NotificationAttributeID::Title, Some(64))), Ok(RequestedAttribute::Title(64)));
+    /// assert_eq!(
+    ///     RequestedAttribute::try_from((NotificationAttributeID::Title, None)),
+    ///     Err(Error::MaxLengthMismatch(NotificationAttributeID::Title)),
+    /// );
+    /// assert_eq!(
+    ///     RequestedAttribute::try_from((NotificationAttributeID::Date, Some(64))),
+    ///     Err(Error::MaxLengthMismatch(NotificationAttributeID::Date)),
+    /// );
+    /// ```
+    fn try_from(original: (NotificationAttributeID, Option<u16>)) -> Result<RequestedAttribute, Error> {
+        match (NotificationAttributeID::from(u8::from(original.0)), original.1) {
+            (NotificationAttributeID::Title, Some(length)) => Ok(RequestedAttribute::Title(length)),
+            (NotificationAttributeID::Subtitle, Some(length)) => Ok(RequestedAttribute::Subtitle(length)),
+            (NotificationAttributeID::Message, Some(length)) => Ok(RequestedAttribute::Message(length)),
+            (NotificationAttributeID::AppIdentifier, None) => Ok(RequestedAttribute::AppIdentifier),
+            (NotificationAttributeID::MessageSize, None) => Ok(RequestedAttribute::MessageSize),
+            (NotificationAttributeID::Date, None) => Ok(RequestedAttribute::Date),
+            (NotificationAttributeID::PositiveActionLabel, None) => Ok(RequestedAttribute::PositiveActionLabel),
+            (NotificationAttributeID::NegativeActionLabel, None) => Ok(RequestedAttribute::NegativeActionLabel),
+            (NotificationAttributeID::Unknown(id), None) => Ok(RequestedAttribute::Unknown(id)),
+            (id, _) => Err(Error::MaxLengthMismatch(id)),
+        }
+    }
+}
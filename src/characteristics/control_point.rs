@@ -1,87 +1,142 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 use crate::attributes::action::ActionID;
+#[cfg(feature = "alloc")]
 use crate::attributes::app::AppAttributeID;
-use crate::attributes::notification::NotificationAttributeID;
+#[cfg(feature = "alloc")]
+use crate::attributes::notification::RequestedAttribute;
 use crate::attributes::command::*;
+#[cfg(feature = "alloc")]
+use crate::decode::many_to_end;
+use crate::decode::Decode;
+#[cfg(feature = "alloc")]
+use crate::encode::{app_identifier, app_identifier_len, to_vec};
+use crate::encode::{Encode, Writer};
+#[cfg(feature = "alloc")]
+use crate::error::utf8;
+use crate::Error;
 
+#[cfg(feature = "alloc")]
+use nom::{
+    bytes::streaming::{take_till},
+    sequence::{terminated},
+};
 use nom::{
-    bytes::complete::{take_till},
-    combinator::{opt, fail},
-    number::complete::le_u16,
-    combinator::{verify},
-    multi::{many0},
-    number::complete::{le_u8, le_u32},
-    branch::{alt},
-    sequence::{pair, terminated},
+    number::streaming::{le_u8, le_u32},
     IResult,
 };
 
 pub const CONTROL_POINT_UUID: &str = "69D1D8F3-45E1-49A8-9821-9BBDFDAAD9D9";
 
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetNotificationAttributesRequest {
-    pub command_id: CommandID,
     pub notification_uid: u32,
-    // Rust doesn't have a clean way to express variadic tuples, and Apple has decided that some attributes need a max_length
-    // assigned. To ensure that users can serialize requests without losing data we're left with this terrible solution of 
-    // having users optionally provide a length or not I can't come up with a good name for a structure here so we're left with this.
-    pub attribute_ids: Vec<(NotificationAttributeID, Option<u16>)>,
+    pub attribute_ids: Vec<RequestedAttribute>,
 }
 
-impl From<GetNotificationAttributesRequest> for Vec<u8> {
-    /// Converts a `GetNotificationAttributesRequest` to a `Vec<u8>`
+#[cfg(feature = "alloc")]
+impl TryFrom<GetNotificationAttributesRequest> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetNotificationAttributesRequest` to a `Vec<u8>`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
-    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// # use ancs::attributes::notification::RequestedAttribute;
     /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
-    /// let notification: GetNotificationAttributesRequest = GetNotificationAttributesRequest {
-    ///    command_id: CommandID::GetNotificationAttributes,
-    ///    notification_uid: 4294967295_u32,
-    ///    attribute_ids: vec![(NotificationAttributeID::AppIdentifier, None), (NotificationAttributeID::Title, Some(u16::MAX))],
-    /// };
+    /// let notification = GetNotificationAttributesRequest::new(
+    ///     4294967295_u32,
+    ///     vec![RequestedAttribute::AppIdentifier, RequestedAttribute::Title(u16::MAX)],
+    /// );
     /// 
-    /// let data: Vec<u8> = notification.into();
+    /// let data: Vec<u8> = notification.try_into().unwrap();
     /// let expected_data: Vec<u8> = vec![0, 255, 255, 255, 255, 0, 1, 255, 255];
     /// 
     /// assert_eq!(data, expected_data)
     /// ```
-    fn from(original: GetNotificationAttributesRequest) -> Vec<u8> {
-        let id = original.command_id as u8;
-        let notification_uid: [u8; 4] = original.notification_uid.to_le_bytes();
-        let mut attribute_ids: Vec<u8> = Vec::new();
+    ///
+    /// An `Unknown` attribute holding the ID of a sized attribute is rejected:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::{NotificationAttributeID, RequestedAttribute};
+    /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+    /// let notification = GetNotificationAttributesRequest::new(1, vec![RequestedAttribute::Unknown(1)]);
+    ///
+    /// let result: Result<Vec<u8>, Error> = notification.try_into();
+    /// assert_eq!(result, Err(Error::MaxLengthMismatch(NotificationAttributeID::Title)));
+    /// ```
+    fn try_from(original: GetNotificationAttributesRequest) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
 
-        original.attribute_ids.into_iter().for_each(|id| {
-            match id.1 {
-                Some(length) => {
-                    let byte: u8 = id.0.into();
-                    let length_bytes: [u8; 2] = length.to_le_bytes();
-                    attribute_ids.push(byte);
-                    attribute_ids.extend(length_bytes);
-                },
-                None => {
-                    let byte: u8 = id.0.into();
-                    attribute_ids.push(byte);
-                }
-            };
-        });
+#[cfg(feature = "alloc")]
+impl Encode for GetNotificationAttributesRequest {
+    fn encoded_len(&self) -> usize {
+        5 + self.attribute_ids.iter().map(Encode::encoded_len).sum::<usize>()
+    }
 
-        let mut v: Vec<u8> = Vec::new();
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetNotificationAttributesRequest::COMMAND_ID.into())?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
 
-        v.push(id);
-        v.extend(notification_uid);
-        v.append(&mut attribute_ids);
+        for attribute in &self.attribute_ids {
+            writer.put_encoded(attribute)?;
+        }
 
-        return v;
+        Ok(writer.finish())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl GetNotificationAttributesRequest {
+    pub const COMMAND_ID: CommandID = CommandID::GetNotificationAttributes;
+
+    /// Creates a request for the `attribute_ids` of the notification identified by `notification_uid`.
+    pub fn new(notification_uid: u32, attribute_ids: Vec<RequestedAttribute>) -> GetNotificationAttributesRequest {
+        GetNotificationAttributesRequest {
+            notification_uid,
+            attribute_ids,
+        }
+    }
+
+    /// Starts building a request for the attributes of the notification identified by `notification_uid`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+    /// let request = GetNotificationAttributesRequest::builder(1)
+    ///     .title(64)
+    ///     .message(256)
+    ///     .date()
+    ///     .positive_action_label()
+    ///     .build();
+    ///
+    /// assert_eq!(request, GetNotificationAttributesRequest::new(1, vec![
+    ///     RequestedAttribute::Title(64),
+    ///     RequestedAttribute::Message(256),
+    ///     RequestedAttribute::Date,
+    ///     RequestedAttribute::PositiveActionLabel,
+    /// ]));
+    /// ```
+    pub fn builder(notification_uid: u32) -> GetNotificationAttributesRequestBuilder {
+        GetNotificationAttributesRequestBuilder {
+            request: GetNotificationAttributesRequest::new(notification_uid, Vec::new()),
+        }
+    }
+
     /// Attempts to parse a `GetNotificationAttributesRequest` from a `&[u8]`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
-    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// # use ancs::attributes::notification::RequestedAttribute;
     /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
     /// let data: Vec<u8> = vec![
     ///     0, 
@@ -96,143 +151,307 @@ impl GetNotificationAttributesRequest {
     /// ];
     /// let (data, notification) = GetNotificationAttributesRequest::parse(&data).unwrap();
     ///
-    /// assert_eq!(notification.command_id, CommandID::GetNotificationAttributes);
     /// assert_eq!(notification.notification_uid, 4294967295_u32);
-    /// assert_eq!(notification.attribute_ids, vec![(NotificationAttributeID::AppIdentifier, None), (NotificationAttributeID::Title, Some(u16::MAX))]);
+    /// assert_eq!(notification.attribute_ids, vec![RequestedAttribute::AppIdentifier, RequestedAttribute::Title(u16::MAX)]);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], GetNotificationAttributesRequest> {
-        let (i, command_id) = CommandID::parse(i)?;
+    ///
+    /// Packets carrying another command are rejected:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::command::CommandID;
+    /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+    /// assert_eq!(
+    ///     GetNotificationAttributesRequest::parse(&[2, 255, 255, 255, 255, 0]),
+    ///     Err(nom::Err::Failure(Error::CommandIDMismatch {
+    ///         expected: CommandID::GetNotificationAttributes,
+    ///         actual: CommandID::PerformNotificationAction,
+    ///     })),
+    /// );
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetNotificationAttributesRequest, Error> {
+        GetNotificationAttributesRequest::decode(i)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for GetNotificationAttributesRequest {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetNotificationAttributesRequest, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetNotificationAttributesRequest::COMMAND_ID)?;
         let (i, notification_uid) = le_u32(i)?;
-        let (i, attribute_ids) = many0(
-            alt((
-                pair(
-                    verify(NotificationAttributeID::parse, |&id| NotificationAttributeID::is_sized(id)),
-                    opt(le_u16),
-                ),
-                pair(
-                    verify(NotificationAttributeID::parse, |&id| !NotificationAttributeID::is_sized(id)),
-                    opt(fail),
-                ),
-            ))
-        )(i)?; 
+        let (i, attribute_ids) = many_to_end(i, RequestedAttribute::decode_streaming)?;
 
         Ok((
             i,
             GetNotificationAttributesRequest {
-                command_id: command_id,
-                notification_uid: notification_uid,
-                attribute_ids: attribute_ids,
+                notification_uid,
+                attribute_ids,
             },
         ))
     }
 }
 
+/// Builds a `GetNotificationAttributesRequest`, see `GetNotificationAttributesRequest::builder`.
+///
+/// Attributes are requested in the order they are added, adding an attribute a second
+/// time replaces the earlier request for it.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct GetNotificationAttributesRequestBuilder {
+    request: GetNotificationAttributesRequest,
+}
+
+#[cfg(feature = "alloc")]
+impl GetNotificationAttributesRequestBuilder {
+    /// Requests any `RequestedAttribute`.
+    pub fn attribute(mut self, attribute: RequestedAttribute) -> GetNotificationAttributesRequestBuilder {
+        let attribute_ids = &mut self.request.attribute_ids;
+
+        match attribute_ids.iter_mut().find(|requested| requested.id() == attribute.id()) {
+            Some(requested) => *requested = attribute,
+            None => attribute_ids.push(attribute),
+        }
+
+        self
+    }
+
+    pub fn app_identifier(self) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::AppIdentifier)
+    }
+
+    /// Requests the title, truncated by iOS to `max_length` bytes.
+    pub fn title(self, max_length: u16) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::Title(max_length))
+    }
+
+    /// Requests the subtitle, truncated by iOS to `max_length` bytes.
+    pub fn subtitle(self, max_length: u16) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::Subtitle(max_length))
+    }
+
+    /// Requests the message, truncated by iOS to `max_length` bytes.
+    pub fn message(self, max_length: u16) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::Message(max_length))
+    }
+
+    pub fn message_size(self) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::MessageSize)
+    }
+
+    pub fn date(self) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::Date)
+    }
+
+    pub fn positive_action_label(self) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::PositiveActionLabel)
+    }
+
+    pub fn negative_action_label(self) -> GetNotificationAttributesRequestBuilder {
+        self.attribute(RequestedAttribute::NegativeActionLabel)
+    }
+
+    /// Requests every attribute defined by ANCS, truncating the sized ones to `max_length` bytes
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+    /// let request = GetNotificationAttributesRequest::builder(1).everything(128).title(32).build();
+    ///
+    /// assert_eq!(request.attribute_ids.len(), 8);
+    /// assert_eq!(request.attribute_ids[1], RequestedAttribute::Title(32));
+    /// assert_eq!(request.attribute_ids[3], RequestedAttribute::Message(128));
+    /// ```
+    pub fn everything(self, max_length: u16) -> GetNotificationAttributesRequestBuilder {
+        self.app_identifier()
+            .title(max_length)
+            .subtitle(max_length)
+            .message(max_length)
+            .message_size()
+            .date()
+            .positive_action_label()
+            .negative_action_label()
+    }
+
+    pub fn build(self) -> GetNotificationAttributesRequest {
+        self.request
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetAppAttributesRequest {
-    pub command_id: CommandID,
     pub app_identifier: String,
     pub attribute_ids: Vec<AppAttributeID>,
 }
 
-impl From<GetAppAttributesRequest> for Vec<u8> {
-    /// Converts a `GetAppAttributesRequest` to a `Vec<u8>`
+#[cfg(feature = "alloc")]
+impl TryFrom<GetAppAttributesRequest> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetAppAttributesRequest` to a `Vec<u8>`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::app::AppAttributeID;
     /// # use ancs::characteristics::control_point::GetAppAttributesRequest;
-    /// let notification: GetAppAttributesRequest = GetAppAttributesRequest {
-    ///     command_id: CommandID::GetNotificationAttributes,
-    ///     app_identifier: "com.apple.test".to_string(),
-    ///     attribute_ids: vec![AppAttributeID::DisplayName],
-    /// };
+    /// let notification = GetAppAttributesRequest::new("com.apple.test", vec![AppAttributeID::DisplayName]);
     ///
-    /// let data: Vec<u8> = notification.into();
-    /// let expected_data: Vec<u8> = vec![0, 99, 111, 109, 46, 97, 112, 112, 108, 101, 46, 116, 101, 115, 116, 0, 0];
+    /// let data: Vec<u8> = notification.try_into().unwrap();
+    /// let expected_data: Vec<u8> = vec![1, 99, 111, 109, 46, 97, 112, 112, 108, 101, 46, 116, 101, 115, 116, 0, 0];
     ///
     /// assert_eq!(data, expected_data)
     /// ```
-    fn from(original: GetAppAttributesRequest) -> Vec<u8> {
-        let mut vec: Vec<u8> = Vec::new();
-
-        // Convert all attributes to bytes
-        let command_id: u8 = original.command_id.into();
-        let mut app_identifier: Vec<u8> = original.app_identifier.into_bytes();
-        let mut attribute_ids: Vec<u8> = original
-            .attribute_ids
-            .into_iter()
-            .map(|id| id.into())
-            .collect();
-
-        // Rust strings are not null terminated by default 
-        // however it is possible that the user knows to insert
-        // a null terminated string of some kind this helps us
-        // ensure that all strings submitted to ANCS are null
-        // terminated UTF-8 byte strings.
-        if app_identifier.last().unwrap() != &0_u8 {
-            app_identifier.push(0);
-        }
+    ///
+    /// An empty `app_identifier` is rejected:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::app::AppAttributeID;
+    /// # use ancs::characteristics::control_point::GetAppAttributesRequest;
+    /// let notification = GetAppAttributesRequest::new(String::new(), vec![AppAttributeID::DisplayName]);
+    ///
+    /// let result: Result<Vec<u8>, Error> = notification.try_into();
+    ///
+    /// assert_eq!(result, Err(Error::EmptyAppIdentifier));
+    /// ```
+    fn try_from(original: GetAppAttributesRequest) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for GetAppAttributesRequest {
+    fn encoded_len(&self) -> usize {
+        1 + app_identifier_len(&self.app_identifier) + self.attribute_ids.len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let app_identifier = app_identifier(&self.app_identifier)?;
 
-        vec.push(command_id);
-        vec.append(&mut app_identifier);
-        vec.append(&mut attribute_ids);
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetAppAttributesRequest::COMMAND_ID.into())?;
+        writer.put(app_identifier)?;
+        writer.put_u8(0)?;
 
-        return vec;
+        for &id in &self.attribute_ids {
+            writer.put_u8(id.into())?;
+        }
+
+        Ok(writer.finish())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl GetAppAttributesRequest {
+    pub const COMMAND_ID: CommandID = CommandID::GetAppAttributes;
+
+    /// Creates a request for the `attribute_ids` of the app identified by `app_identifier`.
+    pub fn new(app_identifier: impl Into<String>, attribute_ids: Vec<AppAttributeID>) -> GetAppAttributesRequest {
+        GetAppAttributesRequest {
+            app_identifier: app_identifier.into(),
+            attribute_ids,
+        }
+    }
+
+    /// Starts building a request for the attributes of the app identified by `app_identifier`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::app::AppAttributeID;
+    /// # use ancs::characteristics::control_point::GetAppAttributesRequest;
+    /// let request = GetAppAttributesRequest::builder("com.apple.test").display_name().build();
+    ///
+    /// assert_eq!(request, GetAppAttributesRequest::new("com.apple.test", vec![AppAttributeID::DisplayName]));
+    /// ```
+    pub fn builder(app_identifier: impl Into<String>) -> GetAppAttributesRequestBuilder {
+        GetAppAttributesRequestBuilder {
+            request: GetAppAttributesRequest::new(app_identifier, Vec::new()),
+        }
+    }
+
     /// Attempts to parse a `GetAppAttributesRequest` from a `&[u8]`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::app::AppAttributeID;
     /// # use ancs::characteristics::control_point::GetAppAttributesRequest;
-    /// let data: Vec<u8> = vec![0, 84, 101, 115, 116, 0, 0];
+    /// let data: Vec<u8> = vec![1, 84, 101, 115, 116, 0, 0];
     /// let (data, notification) = GetAppAttributesRequest::parse(&data).unwrap();
     ///
-    /// assert_eq!(notification.command_id, CommandID::GetNotificationAttributes);
     /// assert_eq!(notification.app_identifier, "Test");
     /// assert_eq!(notification.attribute_ids, vec![AppAttributeID::DisplayName]);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], GetAppAttributesRequest> {
-        let (i, command_id) = CommandID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetAppAttributesRequest, Error> {
+        GetAppAttributesRequest::decode(i)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for GetAppAttributesRequest {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetAppAttributesRequest, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetAppAttributesRequest::COMMAND_ID)?;
         let (i, app_identifier) = terminated(take_till(|b| b == 0), le_u8)(i)?;
-        let (i, attribute_ids) = many0(
-            AppAttributeID::parse
-        )(i)?; 
+        let app_identifier = utf8(app_identifier).map_err(nom::Err::Failure)?;
+        let (i, attribute_ids) = many_to_end(i, AppAttributeID::decode_streaming)?;
 
         Ok((
             i,
             GetAppAttributesRequest {
-                command_id: command_id,
-                app_identifier: String::from_utf8(app_identifier.to_vec()).unwrap(),
-                attribute_ids: attribute_ids,
+                app_identifier,
+                attribute_ids,
             },
         ))
     }
 }
 
+/// Builds a `GetAppAttributesRequest`, see `GetAppAttributesRequest::builder`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct GetAppAttributesRequestBuilder {
+    request: GetAppAttributesRequest,
+}
+
+#[cfg(feature = "alloc")]
+impl GetAppAttributesRequestBuilder {
+    /// Requests any `AppAttributeID`, adding an attribute a second time has no effect.
+    pub fn attribute(mut self, attribute: AppAttributeID) -> GetAppAttributesRequestBuilder {
+        if !self.request.attribute_ids.contains(&attribute) {
+            self.request.attribute_ids.push(attribute);
+        }
+
+        self
+    }
+
+    pub fn display_name(self) -> GetAppAttributesRequestBuilder {
+        self.attribute(AppAttributeID::DisplayName)
+    }
+
+    /// Requests every attribute defined by ANCS.
+    pub fn everything(self) -> GetAppAttributesRequestBuilder {
+        self.display_name()
+    }
+
+    pub fn build(self) -> GetAppAttributesRequest {
+        self.request
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerformNotificationActionRequest {
-    pub command_id: CommandID,
     pub notification_uid: u32,
     pub action_id: ActionID,
 }
 
+#[cfg(feature = "alloc")]
 impl From<PerformNotificationActionRequest> for Vec<u8> {
     /// Converts a `PerformNotificationActionRequest` to a `Vec<u8>`
     ///
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::action::ActionID;
     /// # use ancs::characteristics::control_point::PerformNotificationActionRequest;
-    /// let notification: PerformNotificationActionRequest = PerformNotificationActionRequest {
-    ///     command_id: CommandID::PerformNotificationAction,
-    ///     notification_uid: 4294967295_u32,
-    ///     action_id: ActionID::Positive,
-    /// };
+    /// let notification = PerformNotificationActionRequest::new(4294967295_u32, ActionID::Positive);
     ///
     /// let data: Vec<u8> = notification.into();
     /// let expected_data: Vec<u8> = vec![2, 255, 255, 255, 255, 0];
@@ -240,27 +459,54 @@ impl From<PerformNotificationActionRequest> for Vec<u8> {
     /// assert_eq!(data, expected_data)
     /// ```
     fn from(original: PerformNotificationActionRequest) -> Vec<u8> {
-        let mut vec: Vec<u8> = Vec::new();
+        to_vec(&original).expect("a Vec always has the capacity for a request")
+    }
+}
 
-        // Convert all attributes to bytes
-        let command_id: u8 = original.command_id.into();
-        let notification_uid: [u8; 4] = original.notification_uid.to_le_bytes();
-        let action_id: u8 = original.action_id.into();
+impl Encode for PerformNotificationActionRequest {
+    fn encoded_len(&self) -> usize {
+        6
+    }
 
-        vec.push(command_id);
-        vec.extend(notification_uid);
-        vec.push(action_id);
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(PerformNotificationActionRequest::COMMAND_ID.into())?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
+        writer.put_u8(self.action_id.into())?;
 
-        return vec;
+        Ok(writer.finish())
     }
 }
 
 impl PerformNotificationActionRequest {
+    pub const COMMAND_ID: CommandID = CommandID::PerformNotificationAction;
+
+    /// Creates a request to perform `action_id` on the notification identified by `notification_uid`.
+    pub fn new(notification_uid: u32, action_id: ActionID) -> PerformNotificationActionRequest {
+        PerformNotificationActionRequest {
+            notification_uid,
+            action_id,
+        }
+    }
+
+    /// Starts building a request to perform an action on the notification identified by `notification_uid`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::characteristics::control_point::PerformNotificationActionRequest;
+    /// let request = PerformNotificationActionRequest::builder(1).negative();
+    ///
+    /// assert_eq!(request, PerformNotificationActionRequest::new(1, ActionID::Negative));
+    /// ```
+    pub fn builder(notification_uid: u32) -> PerformNotificationActionRequestBuilder {
+        PerformNotificationActionRequestBuilder { notification_uid }
+    }
+
     /// Attempts to parse a `PerformNotificationActionRequest` from a `&[u8]`
     ///
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::action::ActionID;
     /// # use ancs::characteristics::control_point::PerformNotificationActionRequest;
     /// let data: Vec<u8> = vec![
@@ -273,22 +519,264 @@ impl PerformNotificationActionRequest {
     /// ];
     /// let (data, notification) = PerformNotificationActionRequest::parse(&data).unwrap();
     ///
-    /// assert_eq!(notification.command_id, CommandID::PerformNotificationAction);
     /// assert_eq!(notification.notification_uid, 4294967295_u32);
     /// assert_eq!(notification.action_id, ActionID::Positive);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], PerformNotificationActionRequest> {
-        let (i, command_id) = CommandID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], PerformNotificationActionRequest, Error> {
+        PerformNotificationActionRequest::decode(i)
+    }
+}
+
+impl<'a> Decode<'a> for PerformNotificationActionRequest {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], PerformNotificationActionRequest, Error> {
+        let (i, _) = CommandID::parse_expected(i, PerformNotificationActionRequest::COMMAND_ID)?;
         let (i, notification_uid) = le_u32(i)?;
-        let (i, action_id) = ActionID::parse(i)?;
+        let (i, action_id) = ActionID::decode_streaming(i)?;
 
         Ok((
             i,
             PerformNotificationActionRequest {
-                command_id: command_id,
-                notification_uid: notification_uid,
-                action_id: action_id,
+                notification_uid,
+                action_id,
             },
         ))
     }
 }
+
+/// Builds a `PerformNotificationActionRequest`, see `PerformNotificationActionRequest::builder`.
+///
+/// A request always performs exactly one action, so choosing it finishes the request.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PerformNotificationActionRequestBuilder {
+    notification_uid: u32,
+}
+
+impl PerformNotificationActionRequestBuilder {
+    pub fn action(self, action_id: ActionID) -> PerformNotificationActionRequest {
+        PerformNotificationActionRequest::new(self.notification_uid, action_id)
+    }
+
+    pub fn positive(self) -> PerformNotificationActionRequest {
+        self.action(ActionID::Positive)
+    }
+
+    pub fn negative(self) -> PerformNotificationActionRequest {
+        self.action(ActionID::Negative)
+    }
+}
+
+/// Any request that can be written to the Control Point.
+///
+/// Useful when the command isn't known ahead of time, such as when sniffing or
+/// simulating ANCS traffic.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ControlPointRequest {
+    GetNotificationAttributes(GetNotificationAttributesRequest),
+    GetAppAttributes(GetAppAttributesRequest),
+    PerformNotificationAction(PerformNotificationActionRequest),
+}
+
+#[cfg(feature = "alloc")]
+impl ControlPointRequest {
+    /// Returns the `CommandID` of the request.
+    pub fn command_id(&self) -> CommandID {
+        match self {
+            ControlPointRequest::GetNotificationAttributes(_) => GetNotificationAttributesRequest::COMMAND_ID,
+            ControlPointRequest::GetAppAttributes(_) => GetAppAttributesRequest::COMMAND_ID,
+            ControlPointRequest::PerformNotificationAction(_) => PerformNotificationActionRequest::COMMAND_ID,
+        }
+    }
+
+    /// Attempts to parse any `ControlPointRequest` from a `&[u8]`, picking the request type from its `CommandID`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::attributes::command::CommandID;
+    /// # use ancs::characteristics::control_point::ControlPointRequest;
+    /// let (_, request) = ControlPointRequest::parse(&[2, 255, 255, 255, 255, 0]).unwrap();
+    ///
+    /// match request {
+    ///     ControlPointRequest::PerformNotificationAction(request) => {
+    ///         assert_eq!(request.notification_uid, 4294967295_u32);
+    ///         assert_eq!(request.action_id, ActionID::Positive);
+    ///     }
+    ///     _ => panic!("expected a PerformNotificationActionRequest"),
+    /// }
+    ///
+    /// assert_eq!(ControlPointRequest::parse(&[7, 0]), Err(nom::Err::Failure(Error::UnknownCommandID(7))));
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], ControlPointRequest, Error> {
+        ControlPointRequest::decode(i)
+    }
+
+    /// Attempts to encode the request as the bytes written to the Control Point
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::control_point::ControlPointRequest;
+    /// let data: [u8; 9] = [0, 255, 255, 255, 255, 0, 1, 255, 255];
+    /// let (_, request) = ControlPointRequest::parse(&data).unwrap();
+    ///
+    /// assert_eq!(request.encode().unwrap(), data);
+    /// ```
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        to_vec(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for ControlPointRequest {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], ControlPointRequest, Error> {
+        let (_, command_id) = CommandID::decode_streaming(i)?;
+
+        match command_id {
+            CommandID::GetNotificationAttributes => GetNotificationAttributesRequest::decode_streaming(i)
+                .map(|(i, request)| (i, ControlPointRequest::GetNotificationAttributes(request))),
+            CommandID::GetAppAttributes => GetAppAttributesRequest::decode_streaming(i)
+                .map(|(i, request)| (i, ControlPointRequest::GetAppAttributes(request))),
+            CommandID::PerformNotificationAction => PerformNotificationActionRequest::decode_streaming(i)
+                .map(|(i, request)| (i, ControlPointRequest::PerformNotificationAction(request))),
+            CommandID::Unknown(id) => Err(nom::Err::Failure(Error::UnknownCommandID(id))),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for ControlPointRequest {
+    fn encoded_len(&self) -> usize {
+        match self {
+            ControlPointRequest::GetNotificationAttributes(request) => request.encoded_len(),
+            ControlPointRequest::GetAppAttributes(request) => request.encoded_len(),
+            ControlPointRequest::PerformNotificationAction(request) => request.encoded_len(),
+        }
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        match self {
+            ControlPointRequest::GetNotificationAttributes(request) => request.encode_into(buf),
+            ControlPointRequest::GetAppAttributes(request) => request.encode_into(buf),
+            ControlPointRequest::PerformNotificationAction(request) => request.encode_into(buf),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<ControlPointRequest> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `ControlPointRequest` to a `Vec<u8>`
+    fn try_from(original: ControlPointRequest) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<GetNotificationAttributesRequest> for ControlPointRequest {
+    fn from(original: GetNotificationAttributesRequest) -> ControlPointRequest {
+        ControlPointRequest::GetNotificationAttributes(original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<GetAppAttributesRequest> for ControlPointRequest {
+    fn from(original: GetAppAttributesRequest) -> ControlPointRequest {
+        ControlPointRequest::GetAppAttributes(original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<PerformNotificationActionRequest> for ControlPointRequest {
+    fn from(original: PerformNotificationActionRequest) -> ControlPointRequest {
+        ControlPointRequest::PerformNotificationAction(original)
+    }
+}
+
+/// The errors an iOS device may return when writing to the Control Point.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AncsErrorCode {
+    /// The commandID was not recognized by the NP.
+    UnknownCommand = 0xA0,
+    /// The command was improperly formatted.
+    InvalidCommand = 0xA1,
+    /// One of the parameters (for example, the NotificationUID) does not refer to an existing object on the NP.
+    InvalidParameter = 0xA2,
+    /// The action was not performed.
+    ActionFailed = 0xA3,
+}
+
+impl From<AncsErrorCode> for u8 {
+    /// Converts an `AncsErrorCode` to a `u8`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// let data: u8 = AncsErrorCode::ActionFailed.into();
+    ///
+    /// assert_eq!(0xA3, data);
+    /// ```
+    fn from(original: AncsErrorCode) -> u8 {
+        match original {
+            AncsErrorCode::UnknownCommand => 0xA0,
+            AncsErrorCode::InvalidCommand => 0xA1,
+            AncsErrorCode::InvalidParameter => 0xA2,
+            AncsErrorCode::ActionFailed => 0xA3,
+        }
+    }
+}
+
+impl TryFrom<u8> for AncsErrorCode {
+    type Error = Error;
+
+    /// Attempts to convert the ATT error code of a failed Control Point write to an `AncsErrorCode`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// let error_code: AncsErrorCode = AncsErrorCode::try_from(0xA2).unwrap();
+    ///
+    /// assert_eq!(AncsErrorCode::InvalidParameter, error_code);
+    /// assert_eq!(AncsErrorCode::try_from(0x0E), Err(Error::UnknownErrorCode(0x0E)));
+    /// ```
+    fn try_from(original: u8) -> Result<Self, Self::Error> {
+        match original {
+            0xA0 => Ok(AncsErrorCode::UnknownCommand),
+            0xA1 => Ok(AncsErrorCode::InvalidCommand),
+            0xA2 => Ok(AncsErrorCode::InvalidParameter),
+            0xA3 => Ok(AncsErrorCode::ActionFailed),
+            _ => Err(Error::UnknownErrorCode(original)),
+        }
+    }
+}
+
+impl AncsErrorCode {
+    /// Attempts to parse an `AncsErrorCode` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// let data: [u8; 2] = [0xA0, 0xA1];
+    /// let (data, error_code) = AncsErrorCode::parse(&data).unwrap();
+    ///
+    /// assert_eq!(AncsErrorCode::UnknownCommand, error_code);
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], AncsErrorCode, Error> {
+        AncsErrorCode::decode(i)
+    }
+}
+
+impl<'a> Decode<'a> for AncsErrorCode {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], AncsErrorCode, Error> {
+        let (i, error_code) = le_u8(i)?;
+
+        match AncsErrorCode::try_from(error_code) {
+            Ok(error_code) => Ok((i, error_code)),
+            Err(e) => Err(nom::Err::Failure(e)),
+        }
+    }
+}
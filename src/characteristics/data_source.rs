@@ -1,130 +1,275 @@
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
 use crate::attributes::AppAttribute;
+#[cfg(feature = "alloc")]
 use crate::attributes::NotificationAttribute;
+use crate::attributes::NotificationAttributeRef;
 use crate::attributes::command::*;
+use crate::attributes::notification::NotificationAttributeID;
+#[cfg(feature = "alloc")]
+use crate::decode::many_to_end;
+use crate::decode::Decode;
+#[cfg(feature = "alloc")]
+use crate::characteristics::control_point::{GetAppAttributesRequest, GetNotificationAttributesRequest};
+#[cfg(feature = "alloc")]
+use crate::encode::{app_identifier, app_identifier_len, to_vec};
+use crate::encode::{Encode, Writer};
+#[cfg(feature = "alloc")]
+use crate::error::{utf8, utf8_str};
+use crate::error::Error;
 
-use nom::combinator::all_consuming;
+#[cfg(feature = "alloc")]
 use nom::{
-    bytes::complete::take_till,
-    multi::{many0},
-    number::complete::{le_u8, le_u32},
+    bytes::streaming::take_till,
+    number::streaming::le_u8,
     sequence::{terminated},
+};
+use nom::{
+    number::streaming::le_u32,
     IResult,
 };
 
 pub const DATA_SOURCE_UUID: &str = "22EAC6E9-24D6-4BB5-BE44-B36ACE7C7BFB";
 
+#[cfg(feature = "alloc")]
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetNotificationAttributesResponse {
-    pub command_id: CommandID,
     pub notification_uid: u32,
     pub attribute_list: Vec<NotificationAttribute>,
 }
 
-impl From<GetNotificationAttributesResponse> for Vec<u8> {
-    /// Converts a `GetNotificationAttributesResponse` to a `Vec<u8>`
+#[cfg(feature = "alloc")]
+impl TryFrom<GetNotificationAttributesResponse> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetNotificationAttributesResponse` to a `Vec<u8>`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::NotificationAttribute;
     /// # use ancs::attributes::notification::NotificationAttributeID;
     /// # use ancs::characteristics::data_source::GetNotificationAttributesResponse;
     /// let notification: GetNotificationAttributesResponse = GetNotificationAttributesResponse {
-    ///     command_id: CommandID::GetNotificationAttributes,
     ///     notification_uid: 4294967295_u32,
     ///     attribute_list: vec![
     ///         NotificationAttribute { 
     ///             id: NotificationAttributeID::AppIdentifier, 
     ///             length: "com.rust.test".to_string().as_bytes().len() as u16, 
-    ///             value: Some("com.rust.test".to_string()) 
+    ///             value: Some("com.rust.test".into()) 
     ///         }
     ///     ],
     /// };
     ///
-    /// let data: Vec<u8> = notification.into();
+    /// let data: Vec<u8> = notification.try_into().unwrap();
     /// let expected_data: Vec<u8> = vec![0, 255, 255, 255, 255, 0, 13, 0, 99, 111, 109, 46, 114, 117, 115, 116, 46, 116, 101, 115, 116];
     /// 
     /// assert_eq!(data, expected_data)
     /// ```
-    fn from(original: GetNotificationAttributesResponse) -> Vec<u8> {
-        let mut vec: Vec<u8> = Vec::new();
-
-        // Convert all attributes to bytes
-        let command_id: u8 = original.command_id.into();
-        let notification_uid: [u8; 4] = original.notification_uid.to_le_bytes();
-        let mut attribute_ids: Vec<u8> = original
-            .attribute_list
-            .into_iter()
-            .map(|attribute| attribute.into())
-            .into_iter()
-            .flat_map(|att: Vec<u8>| att)
-            .collect();
-
-        vec.push(command_id);
-        vec.append(&mut notification_uid.into());
-        vec.append(&mut attribute_ids);
-
-        return vec;
+    fn try_from(original: GetNotificationAttributesResponse) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for GetNotificationAttributesResponse {
+    fn encoded_len(&self) -> usize {
+        5 + self.attribute_list.iter().map(Encode::encoded_len).sum::<usize>()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetNotificationAttributesResponse::COMMAND_ID.into())?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
+
+        for attribute in &self.attribute_list {
+            writer.put_encoded(attribute)?;
+        }
+
+        Ok(writer.finish())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl GetNotificationAttributesResponse {
+    pub const COMMAND_ID: CommandID = CommandID::GetNotificationAttributes;
+
     /// Attempts to parse a `GetNotificationAttributesResponse` from a `&[u8]`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::NotificationAttribute;
     /// # use ancs::attributes::notification::NotificationAttributeID;
     /// # use ancs::characteristics::data_source::GetNotificationAttributesResponse;
     /// let bytes: Vec<u8> = vec![0, 255, 255, 255, 255, 0, 13, 0, 99, 111, 109, 46, 114, 117, 115, 116, 46, 116, 101, 115, 116];
     /// let notification = GetNotificationAttributesResponse::parse(&bytes).unwrap();
     ///
-    /// assert_eq!(notification.1.command_id, CommandID::GetNotificationAttributes);
     /// assert_eq!(notification.1.notification_uid, 4294967295_u32);
     /// assert_eq!(notification.1.attribute_list, vec![
     ///    NotificationAttribute { 
     ///        id: NotificationAttributeID::AppIdentifier, 
     ///        length: "com.rust.test".to_string().as_bytes().len() as u16, 
-    ///        value: Some("com.rust.test".to_string()) 
+    ///        value: Some("com.rust.test".into()) 
     ///    }
     /// ]);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], GetNotificationAttributesResponse> {
-        let (i, command_id) = CommandID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetNotificationAttributesResponse, Error> {
+        GetNotificationAttributesResponse::decode(i)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for GetNotificationAttributesResponse {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetNotificationAttributesResponse, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetNotificationAttributesResponse::COMMAND_ID)?;
         let (i, notification_uid) = le_u32(i)?;
-        let (i, attribute_list) = all_consuming(many0(NotificationAttribute::parse))(i)?;
+        let (i, attribute_list) = many_to_end(i, NotificationAttribute::decode_streaming)?;
 
         Ok((
             i,
             GetNotificationAttributesResponse {
-                command_id: command_id,
-                notification_uid: notification_uid,
-                attribute_list: attribute_list,
+                notification_uid,
+                attribute_list,
             },
         ))
     }
 }
 
+/// A `GetNotificationAttributesResponse` borrowing its attributes from the buffer it was parsed from.
+///
+/// Every attribute is validated by `parse`, after which they can be read through
+/// `attributes` without allocating or copying any of their values.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GetNotificationAttributesResponseRef<'a> {
+    pub notification_uid: u32,
+    attribute_list: &'a [u8],
+}
+
+impl<'a> GetNotificationAttributesResponseRef<'a> {
+    pub const COMMAND_ID: CommandID = CommandID::GetNotificationAttributes;
+
+    /// Attempts to parse a `GetNotificationAttributesResponseRef` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// # use ancs::characteristics::data_source::GetNotificationAttributesResponseRef;
+    /// let bytes: [u8; 21] = [0, 255, 255, 255, 255, 0, 13, 0, 99, 111, 109, 46, 114, 117, 115, 116, 46, 116, 101, 115, 116];
+    /// let (_, response) = GetNotificationAttributesResponseRef::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(response.notification_uid, 4294967295_u32);
+    /// assert_eq!(response.attribute(NotificationAttributeID::AppIdentifier).unwrap().value, "com.rust.test");
+    /// ```
+    ///
+    /// Malformed attributes are reported by `parse` rather than while iterating:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::characteristics::data_source::GetNotificationAttributesResponseRef;
+    /// let bytes: [u8; 10] = [0, 1, 0, 0, 0, 1, 4, 0, 116, 101];
+    ///
+    /// assert_eq!(GetNotificationAttributesResponseRef::parse(&bytes), Err(nom::Err::Error(Error::Truncated)));
+    /// ```
+    pub fn parse(i: &'a [u8]) -> IResult<&'a [u8], GetNotificationAttributesResponseRef<'a>, Error> {
+        GetNotificationAttributesResponseRef::decode(i)
+    }
+
+    /// Returns an iterator over the attributes in the order they were received.
+    pub fn attributes(&self) -> impl Iterator<Item = NotificationAttributeRef<'a>> {
+        let mut rest = self.attribute_list;
+
+        core::iter::from_fn(move || {
+            // The attributes were validated by `parse` so none of them can fail here.
+            let (i, attribute) = NotificationAttributeRef::parse(rest).ok()?;
+            rest = i;
+            Some(attribute)
+        })
+    }
+
+    /// Returns the first attribute with the given `id`, if any.
+    pub fn attribute(&self, id: NotificationAttributeID) -> Option<NotificationAttributeRef<'a>> {
+        self.attributes().find(|attribute| attribute.id == id)
+    }
+
+    /// Converts a `GetNotificationAttributesResponseRef` to an owned `GetNotificationAttributesResponse`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::data_source::{GetNotificationAttributesResponse, GetNotificationAttributesResponseRef};
+    /// let bytes: [u8; 21] = [0, 255, 255, 255, 255, 0, 13, 0, 99, 111, 109, 46, 114, 117, 115, 116, 46, 116, 101, 115, 116];
+    /// let (_, response) = GetNotificationAttributesResponseRef::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(response.to_owned(), GetNotificationAttributesResponse::parse(&bytes).unwrap().1);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_owned(self) -> GetNotificationAttributesResponse {
+        GetNotificationAttributesResponse {
+            notification_uid: self.notification_uid,
+            attribute_list: self.attributes().map(NotificationAttributeRef::to_owned).collect(),
+        }
+    }
+}
+
+impl<'a> Decode<'a> for GetNotificationAttributesResponseRef<'a> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetNotificationAttributesResponseRef<'a>, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetNotificationAttributesResponseRef::COMMAND_ID)?;
+        let (attribute_list, notification_uid) = le_u32(i)?;
+
+        let mut rest = attribute_list;
+        while !rest.is_empty() {
+            rest = NotificationAttributeRef::decode_streaming(rest)?.0;
+        }
+
+        Ok((
+            rest,
+            GetNotificationAttributesResponseRef {
+                notification_uid,
+                attribute_list,
+            },
+        ))
+    }
+}
+
+impl Encode for GetNotificationAttributesResponseRef<'_> {
+    fn encoded_len(&self) -> usize {
+        5 + self.attribute_list.len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetNotificationAttributesResponseRef::COMMAND_ID.into())?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
+        // The attributes were validated by `parse` so they are written back as received.
+        writer.put(self.attribute_list)?;
+
+        Ok(writer.finish())
+    }
+}
+
+#[cfg(feature = "alloc")]
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetAppAttributesResponse {
-    pub command_id: CommandID,
     pub app_identifier: String,
     pub attribute_list: Vec<AppAttribute>,
 }
 
-impl From<GetAppAttributesResponse> for Vec<u8> {
-    /// Converts a `GetAppAttributesResponse` to a `Vec<u8>`
+#[cfg(feature = "alloc")]
+impl TryFrom<GetAppAttributesResponse> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetAppAttributesResponse` to a `Vec<u8>`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::AppAttribute;
     /// # use ancs::attributes::app::AppAttributeID;
     /// # use ancs::characteristics::data_source::GetAppAttributesResponse;
     /// 
     /// let response: GetAppAttributesResponse = GetAppAttributesResponse {
-    ///     command_id: CommandID::GetAppAttributes,
     ///     app_identifier: "com.apple.test".to_string(),
     ///     attribute_list: vec![
     ///         AppAttribute { 
@@ -160,47 +305,45 @@ impl From<GetAppAttributesResponse> for Vec<u8> {
     ///     115,
     ///     116
     /// ];
-    /// let data: Vec<u8> = response.into();
+    /// let data: Vec<u8> = response.try_into().unwrap();
     /// 
     /// assert_eq!(data, expected_data)
     /// ```
-    fn from(original: GetAppAttributesResponse) -> Vec<u8> {
-        let mut vec: Vec<u8> = Vec::new();
-        
-        // Convert all attributes to bytes
-        let command_id: u8 = original.command_id.into();
-        let mut app_identifier: Vec<u8> = original.app_identifier.as_bytes().to_vec();
-        let mut attribute_ids: Vec<u8> = original
-            .attribute_list
-            .into_iter()
-            .map(|attribute| attribute.into())
-            .into_iter()
-            .flat_map(|att: Vec<u8>| att)
-            .collect();
-
-        // Rust strings are not null terminated by default 
-        // however it is possible that the user knows to insert
-        // a null terminated string of some kind this helps us
-        // ensure that all strings submitted to ANCS are null
-        // terminated UTF-8 byte strings.
-        if app_identifier.last().unwrap() != &0_u8 {
-            app_identifier.push(0);
-        }
+    fn try_from(original: GetAppAttributesResponse) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for GetAppAttributesResponse {
+    fn encoded_len(&self) -> usize {
+        1 + app_identifier_len(&self.app_identifier) + self.attribute_list.iter().map(Encode::encoded_len).sum::<usize>()
+    }
 
-        vec.push(command_id);
-        vec.append(&mut app_identifier.into());
-        vec.append(&mut attribute_ids);
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let app_identifier = app_identifier(&self.app_identifier)?;
+
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetAppAttributesResponse::COMMAND_ID.into())?;
+        writer.put(app_identifier)?;
+        writer.put_u8(0)?;
+
+        for attribute in &self.attribute_list {
+            writer.put_encoded(attribute)?;
+        }
 
-        return vec;
+        Ok(writer.finish())
     }
 }
 
+#[cfg(feature = "alloc")]
 impl GetAppAttributesResponse {
+    pub const COMMAND_ID: CommandID = CommandID::GetAppAttributes;
+
     /// Attempts to parse a `GetAppAttributesResponse` from a `&[u8]`
     /// 
     /// # Examples
     /// ```
-    /// # use ancs::attributes::command::CommandID;
     /// # use ancs::attributes::AppAttribute;
     /// # use ancs::attributes::app::AppAttributeID;
     /// # use ancs::characteristics::data_source::GetAppAttributesResponse;
@@ -233,7 +376,6 @@ impl GetAppAttributesResponse {
     /// 
     /// let (data, response) = GetAppAttributesResponse::parse(&data).unwrap();
     ///
-    /// assert_eq!(response.command_id, CommandID::GetAppAttributes);
     /// assert_eq!(response.app_identifier, "com.apple.test");
     /// assert_eq!(response.attribute_list, vec![
     ///    AppAttribute { 
@@ -243,18 +385,320 @@ impl GetAppAttributesResponse {
     ///    }
     /// ]);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], GetAppAttributesResponse> {
-        let (i, command_id) = CommandID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetAppAttributesResponse, Error> {
+        GetAppAttributesResponse::decode(i)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for GetAppAttributesResponse {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetAppAttributesResponse, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetAppAttributesResponse::COMMAND_ID)?;
         let (i, app_identifier) = terminated(take_till(|b| b == 0), le_u8)(i)?;
-        let (i, attribute_list) = all_consuming(many0(AppAttribute::parse))(i)?;
+        let app_identifier = utf8(app_identifier).map_err(nom::Err::Failure)?;
+        let (i, attribute_list) = many_to_end(i, AppAttribute::decode_streaming)?;
 
         Ok((
             i,
             GetAppAttributesResponse {
-                command_id: command_id,
-                app_identifier: String::from_utf8(app_identifier.to_vec()).unwrap(),
-                attribute_list: attribute_list,
+                app_identifier,
+                attribute_list,
             },
         ))
     }
 }
+
+/// A complete response received on the Data Source characteristic.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataSourceResponse {
+    GetNotificationAttributes(GetNotificationAttributesResponse),
+    GetAppAttributes(GetAppAttributesResponse),
+}
+
+#[cfg(feature = "alloc")]
+impl DataSourceResponse {
+    /// Returns the `CommandID` of the request the response answers.
+    pub fn command_id(&self) -> CommandID {
+        match self {
+            DataSourceResponse::GetNotificationAttributes(_) => GetNotificationAttributesResponse::COMMAND_ID,
+            DataSourceResponse::GetAppAttributes(_) => GetAppAttributesResponse::COMMAND_ID,
+        }
+    }
+
+    /// Attempts to parse any complete `DataSourceResponse` from a `&[u8]`, picking the response
+    /// type from its `CommandID`. Commands that don't have a Data Source response, such as
+    /// `CommandID::PerformNotificationAction`, are rejected as unknown.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::characteristics::data_source::DataSourceResponse;
+    /// let data: [u8; 9] = [1, 97, 0, 0, 2, 0, 104, 105, 0];
+    ///
+    /// match DataSourceResponse::parse(&data[..8]).unwrap().1 {
+    ///     DataSourceResponse::GetAppAttributes(response) => {
+    ///         assert_eq!(response.app_identifier, "a");
+    ///         assert_eq!(response.attribute_list[0].value, Some("hi".to_string()));
+    ///     }
+    ///     _ => panic!("expected a GetAppAttributesResponse"),
+    /// }
+    ///
+    /// assert_eq!(DataSourceResponse::parse(&[2, 0, 0, 0, 0]), Err(nom::Err::Failure(Error::UnknownCommandID(2))));
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], DataSourceResponse, Error> {
+        DataSourceResponse::decode(i)
+    }
+
+    /// Attempts to encode the response as the bytes sent on the Data Source
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::data_source::DataSourceResponse;
+    /// let data: [u8; 8] = [1, 97, 0, 0, 2, 0, 104, 105];
+    /// let (_, response) = DataSourceResponse::parse(&data).unwrap();
+    ///
+    /// assert_eq!(response.encode().unwrap(), data);
+    /// ```
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        to_vec(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Decode<'a> for DataSourceResponse {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], DataSourceResponse, Error> {
+        let (_, command_id) = CommandID::decode_streaming(i)?;
+
+        match command_id {
+            CommandID::GetNotificationAttributes => GetNotificationAttributesResponse::decode_streaming(i)
+                .map(|(i, response)| (i, DataSourceResponse::GetNotificationAttributes(response))),
+            CommandID::GetAppAttributes => GetAppAttributesResponse::decode_streaming(i)
+                .map(|(i, response)| (i, DataSourceResponse::GetAppAttributes(response))),
+            command_id => Err(nom::Err::Failure(Error::UnknownCommandID(command_id.into()))),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Encode for DataSourceResponse {
+    fn encoded_len(&self) -> usize {
+        match self {
+            DataSourceResponse::GetNotificationAttributes(response) => response.encoded_len(),
+            DataSourceResponse::GetAppAttributes(response) => response.encoded_len(),
+        }
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        match self {
+            DataSourceResponse::GetNotificationAttributes(response) => response.encode_into(buf),
+            DataSourceResponse::GetAppAttributes(response) => response.encode_into(buf),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<DataSourceResponse> for Vec<u8> {
+    type Error = Error;
+
+    /// Attempts to convert a `DataSourceResponse` to a `Vec<u8>`
+    fn try_from(original: DataSourceResponse) -> Result<Vec<u8>, Error> {
+        to_vec(&original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<GetNotificationAttributesResponse> for DataSourceResponse {
+    fn from(original: GetNotificationAttributesResponse) -> DataSourceResponse {
+        DataSourceResponse::GetNotificationAttributes(original)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<GetAppAttributesResponse> for DataSourceResponse {
+    fn from(original: GetAppAttributesResponse) -> DataSourceResponse {
+        DataSourceResponse::GetAppAttributes(original)
+    }
+}
+
+/// The header a reassembled response must carry, taken from the originating request.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+enum ExpectedHeader {
+    NotificationUID(u32),
+    AppIdentifier(String),
+}
+
+/// Reassembles a Data Source response that iOS split across several GATT notifications.
+///
+/// A reassembler is created from the request that was written to the Control Point,
+/// which tells it how many attributes the response will contain. Chunks are then
+/// pushed in the order they arrive and the typed response is returned once every
+/// requested attribute has been received. After an error the reassembler should be
+/// discarded as the remaining chunks of the response can no longer be trusted.
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DataSourceReassembler {
+    command_id: CommandID,
+    header: ExpectedHeader,
+    attribute_count: usize,
+    buffer: Vec<u8>,
+    complete: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl From<&GetNotificationAttributesRequest> for DataSourceReassembler {
+    fn from(original: &GetNotificationAttributesRequest) -> DataSourceReassembler {
+        DataSourceReassembler {
+            command_id: CommandID::GetNotificationAttributes,
+            header: ExpectedHeader::NotificationUID(original.notification_uid),
+            attribute_count: original.attribute_ids.len(),
+            buffer: Vec::new(),
+            complete: false,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&GetAppAttributesRequest> for DataSourceReassembler {
+    fn from(original: &GetAppAttributesRequest) -> DataSourceReassembler {
+        DataSourceReassembler {
+            command_id: CommandID::GetAppAttributes,
+            header: ExpectedHeader::AppIdentifier(original.app_identifier.trim_end_matches('\0').to_string()),
+            attribute_count: original.attribute_ids.len(),
+            buffer: Vec::new(),
+            complete: false,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DataSourceReassembler {
+    /// Appends a chunk received on the Data Source and returns the response once it is complete
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+    /// # use ancs::characteristics::data_source::{DataSourceReassembler, DataSourceResponse};
+    /// let request = GetNotificationAttributesRequest::new(1, vec![RequestedAttribute::AppIdentifier, RequestedAttribute::Title(16)]);
+    /// let mut reassembler = DataSourceReassembler::from(&request);
+    ///
+    /// assert_eq!(reassembler.push(&[0, 1, 0, 0, 0, 0, 4, 0, 116]), Ok(None));
+    /// assert_eq!(reassembler.push(&[101, 115, 116, 1, 2]), Ok(None));
+    ///
+    /// match reassembler.push(&[0, 104, 105]).unwrap() {
+    ///     Some(DataSourceResponse::GetNotificationAttributes(response)) => {
+    ///         assert_eq!(response.notification_uid, 1);
+    ///         assert_eq!(response.attribute_list[0].value, Some("test".into()));
+    ///         assert_eq!(response.attribute_list[1].value, Some("hi".into()));
+    ///     }
+    ///     _ => panic!("expected a complete response"),
+    /// }
+    /// ```
+    ///
+    /// Responses for another notification or carrying extra bytes are rejected:
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+    /// # use ancs::characteristics::data_source::DataSourceReassembler;
+    /// let request = GetNotificationAttributesRequest::new(1, vec![RequestedAttribute::AppIdentifier]);
+    ///
+    /// let mut reassembler = DataSourceReassembler::from(&request);
+    /// assert_eq!(reassembler.push(&[0, 2, 0, 0, 0]), Err(Error::NotificationUIDMismatch { expected: 1, actual: 2 }));
+    ///
+    /// let mut reassembler = DataSourceReassembler::from(&request);
+    /// assert_eq!(reassembler.push(&[0, 1, 0, 0, 0, 0, 0, 0, 7]), Err(Error::ResponseOverflow { excess: 1 }));
+    /// ```
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Option<DataSourceResponse>, Error> {
+        if self.complete {
+            return Err(Error::ResponseOverflow { excess: chunk.len() });
+        }
+
+        self.buffer.extend_from_slice(chunk);
+
+        let end = match self.response_end() {
+            Ok(Some(end)) => end,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                self.buffer.clear();
+                return Err(e);
+            }
+        };
+
+        if end < self.buffer.len() {
+            let excess = self.buffer.len() - end;
+            self.buffer.clear();
+            return Err(Error::ResponseOverflow { excess });
+        }
+
+        self.complete = true;
+        let buffer = core::mem::take(&mut self.buffer);
+
+        Ok(Some(DataSourceResponse::parse(&buffer)?.1))
+    }
+
+    /// Determines if a full response has been returned by `push`.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Validates the buffered header and walks the attributes received so far,
+    /// returning the length of the full response once every attribute is present.
+    fn response_end(&self) -> Result<Option<usize>, Error> {
+        let command_id = match self.buffer.first() {
+            Some(&id) => CommandID::from(id),
+            None => return Ok(None),
+        };
+
+        if command_id != self.command_id {
+            return Err(Error::CommandIDMismatch { expected: self.command_id, actual: command_id });
+        }
+
+        let mut offset = match &self.header {
+            ExpectedHeader::NotificationUID(expected) => {
+                let uid = match self.buffer.get(1..5) {
+                    Some(uid) => u32::from_le_bytes([uid[0], uid[1], uid[2], uid[3]]),
+                    None => return Ok(None),
+                };
+
+                if uid != *expected {
+                    return Err(Error::NotificationUIDMismatch { expected: *expected, actual: uid });
+                }
+
+                5
+            }
+            ExpectedHeader::AppIdentifier(expected) => {
+                let length = match self.buffer[1..].iter().position(|&b| b == 0) {
+                    Some(length) => length,
+                    None => return Ok(None),
+                };
+                let app_identifier = utf8_str(&self.buffer[1..1 + length])?;
+
+                if app_identifier != expected {
+                    return Err(Error::AppIdentifierMismatch);
+                }
+
+                length + 2
+            }
+        };
+
+        // Each attribute is an ID byte followed by a little endian u16 length and the value.
+        for _ in 0..self.attribute_count {
+            let length = match self.buffer.get(offset + 1..offset + 3) {
+                Some(length) => u16::from_le_bytes([length[0], length[1]]) as usize,
+                None => return Ok(None),
+            };
+
+            offset += 3 + length;
+
+            if offset > self.buffer.len() {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(offset))
+    }
+}
@@ -1,14 +1,19 @@
 use crate::attributes::category::*;
 use crate::attributes::event::*;
 
-use nom::{multi::count, number::complete::le_u8, IResult};
+use crate::decode::Decode;
+use crate::encode::{Encode, Writer};
+use crate::Error;
+
+use nom::{number::streaming::{le_u8, le_u32}, IResult};
 
 pub const NOTIFICATION_SOURCE_UUID: &str = "9FBF120D-6301-42D9-8C58-25E699A21DBD";
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Notification {
     pub event_id: EventID,
-    pub event_flags: EventFlag,
+    pub event_flags: EventFlags,
     pub category_id: CategoryID,
     pub category_count: u8,
     pub notification_uid: u32,
@@ -25,7 +30,7 @@ impl Notification {
     /// # use ancs::characteristics::notification_source::Notification;
     /// let notification: Notification = Notification {
     ///     event_id: EventID::NotificationAdded,
-    ///     event_flags: EventFlag::Silent,
+    ///     event_flags: EventFlag::Important | EventFlag::PositiveAction | EventFlag::NegativeAction,
     ///     category_id: CategoryID::Other,
     ///     category_count: 0,
     ///     notification_uid: 4294967295_u32,
@@ -36,26 +41,61 @@ impl Notification {
     /// let parsed_notification = Notification::parse(&notification_bytes).unwrap().1;
     /// 
     /// assert_eq!(parsed_notification.event_id, EventID::NotificationAdded);
-    /// assert_eq!(parsed_notification.event_flags, EventFlag::Silent);
+    /// assert!(parsed_notification.event_flags.contains(EventFlag::Important));
+    /// assert!(parsed_notification.event_flags.contains(EventFlag::PositiveAction | EventFlag::NegativeAction));
+    /// assert!(!parsed_notification.event_flags.contains(EventFlag::Silent));
     /// assert_eq!(parsed_notification.category_id, CategoryID::Other);
     /// assert_eq!(parsed_notification.category_count, 0);
     /// assert_eq!(parsed_notification.notification_uid, 4294967295_u32);
     /// ```
-    pub fn parse(i: &[u8]) -> IResult<&[u8], Notification> {
-        let (i, event_id) = EventID::parse(i)?;
-        let (i, event_flags) = EventFlag::parse(i)?;
-        let (i, category_id) = CategoryID::parse(i)?;
+    pub fn parse(i: &[u8]) -> IResult<&[u8], Notification, Error> {
+        Notification::decode(i)
+    }
+
+    /// Attempts to parse a `Notification` from a `&[u8]`, rejecting event and category IDs
+    /// that aren't defined by ANCS
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::category::CategoryID;
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// let bytes: [u8; 8] = [0, 0, 42, 1, 1, 0, 0, 0];
+    ///
+    /// assert_eq!(Notification::parse(&bytes).unwrap().1.category_id, CategoryID::Unknown(42));
+    /// assert_eq!(Notification::parse_strict(&bytes), Err(nom::Err::Failure(Error::UnknownCategoryID(42))));
+    /// ```
+    pub fn parse_strict(i: &[u8]) -> IResult<&[u8], Notification, Error> {
+        let (i, notification) = Notification::parse(i)?;
+
+        if let EventID::Unknown(id) = notification.event_id {
+            return Err(nom::Err::Failure(Error::UnknownEventID(id)));
+        }
+
+        if let CategoryID::Unknown(id) = notification.category_id {
+            return Err(nom::Err::Failure(Error::UnknownCategoryID(id)));
+        }
+
+        Ok((i, notification))
+    }
+}
+
+impl<'a> Decode<'a> for Notification {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], Notification, Error> {
+        let (i, event_id) = EventID::decode_streaming(i)?;
+        let (i, event_flags) = EventFlags::decode_streaming(i)?;
+        let (i, category_id) = CategoryID::decode_streaming(i)?;
         let (i, category_count) = le_u8(i)?;
-        let (i, notification_uid) = count(le_u8, 4)(i)?;
+        let (i, notification_uid) = le_u32(i)?;
 
         Ok((
             i,
             Notification {
-                event_id: event_id,
-                event_flags: event_flags,
-                category_id: category_id,
+                event_id,
+                event_flags,
+                category_id,
                 category_count,
-                notification_uid: u32::from_le_bytes(notification_uid.try_into().unwrap()),
+                notification_uid,
             },
         ))
     }
@@ -67,12 +107,12 @@ impl From<Notification> for [u8; 8] {
     /// # Examples
     /// ```
     /// # use ancs::attributes::category::CategoryID;
-    /// # use ancs::attributes::event::EventFlag;
+    /// # use ancs::attributes::event::{EventFlag, EventFlags};
     /// # use ancs::attributes::event::EventID;
     /// # use ancs::characteristics::notification_source::Notification;
     /// let notification: Notification = Notification {
     ///    event_id: EventID::NotificationAdded,
-    ///    event_flags: EventFlag::Silent,
+    ///    event_flags: EventFlags::from(EventFlag::Silent),
     ///    category_id: CategoryID::Other,
     ///    category_count: 0,
     ///    notification_uid: 4294967295_u32,
@@ -83,21 +123,28 @@ impl From<Notification> for [u8; 8] {
     ///
     ///assert_eq!(notification_bytes, expected_bytes)
     /// ```
-
     fn from(original: Notification) -> [u8; 8] {
         let mut bytes: [u8; 8] = [0; 8];
-        let uid_as_u8 = original.notification_uid.to_le_bytes();
 
-        bytes[0] = original.event_id as u8;
-        bytes[1] = original.event_flags as u8;
-        bytes[2] = original.category_id as u8;
-        bytes[3] = original.category_count as u8;
+        original.encode_into(&mut bytes).expect("a Notification is always 8 bytes");
+
+        bytes
+    }
+}
+
+impl Encode for Notification {
+    fn encoded_len(&self) -> usize {
+        8
+    }
 
-        bytes[4] = uid_as_u8[0];
-        bytes[5] = uid_as_u8[1];
-        bytes[6] = uid_as_u8[2];
-        bytes[7] = uid_as_u8[3];
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(self.event_id.into())?;
+        writer.put_u8(self.event_flags.into())?;
+        writer.put_u8(self.category_id.into())?;
+        writer.put_u8(self.category_count)?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
 
-        return bytes;
+        Ok(writer.finish())
     }
 }
@@ -0,0 +1,620 @@
+//! ## Client
+//!
+//! The `AncsClient` is a transport agnostic (sans-IO) state machine implementing the
+//! ANCS session logic on top of the packet types found in `attributes` and `characteristics`.
+//! It never touches Bluetooth itself, instead the caller feeds it the bytes received on the
+//! Notification Source and Data Source characteristics, writes whatever it hands out to the
+//! Control Point and reads back high-level `ClientEvent`s.
+//!
+//! ANCS only allows a single command to be in flight at a time, the client queues every
+//! command and only hands out the next Control Point write once the previous command has
+//! completed. Callers should drain `poll_transmit` and `poll_event` after every call that
+//! feeds the client new data.
+//!
+//! A command whose response never arrives would hold up every command queued behind it.
+//! The client doesn't keep time itself, callers track a deadline for each write, for example
+//! with a `PendingCommands`, and abort the command with `cancel_in_flight` once it passes.
+//!
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::attributes::action::ActionID;
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::command::CommandID;
+use crate::attributes::date::DateTime;
+use crate::attributes::event::EventID;
+use crate::attributes::notification::{NotificationAttributeID, RequestedAttribute};
+use crate::attributes::{NotificationAttribute, NotificationAttributeValue};
+#[cfg(feature = "std")]
+use crate::characteristics::control_point::ControlPointRequest;
+use crate::characteristics::control_point::{
+    AncsErrorCode, GetAppAttributesRequest, GetNotificationAttributesRequest, PerformNotificationActionRequest,
+};
+use crate::characteristics::data_source::{
+    DataSourceReassembler, DataSourceResponse, GetAppAttributesResponse, GetNotificationAttributesResponse,
+};
+use crate::characteristics::notification_source::Notification;
+use crate::encode::{to_vec, Encode};
+use crate::Error;
+
+#[cfg(feature = "async")]
+mod asynchronous;
+#[cfg(feature = "std")]
+mod blocking;
+#[cfg(feature = "std")]
+mod driver;
+
+#[cfg(feature = "async")]
+pub use asynchronous::AsyncAncsClient;
+#[cfg(feature = "std")]
+pub use blocking::SyncAncsClient;
+
+/// A `Notification` together with the attributes fetched for it.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResolvedNotification {
+    pub notification: Notification,
+    pub attributes: Vec<NotificationAttribute>,
+}
+
+impl ResolvedNotification {
+    /// Returns the value of an attribute if it was fetched and is not empty.
+    pub fn attribute(&self, id: NotificationAttributeID) -> Option<&NotificationAttributeValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.id == id)
+            .and_then(|attribute| attribute.value.as_ref())
+            .filter(|value| value.as_str() != Some(""))
+    }
+
+    pub fn app_identifier(&self) -> Option<&str> {
+        self.attribute(NotificationAttributeID::AppIdentifier).and_then(NotificationAttributeValue::as_str)
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.attribute(NotificationAttributeID::Title).and_then(NotificationAttributeValue::as_str)
+    }
+
+    pub fn subtitle(&self) -> Option<&str> {
+        self.attribute(NotificationAttributeID::Subtitle).and_then(NotificationAttributeValue::as_str)
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.attribute(NotificationAttributeID::Message).and_then(NotificationAttributeValue::as_str)
+    }
+
+    pub fn date(&self) -> Option<DateTime> {
+        self.attribute(NotificationAttributeID::Date).and_then(NotificationAttributeValue::as_date)
+    }
+}
+
+/// The high-level events produced by an `AncsClient`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClientEvent {
+    /// A notification was added and its attributes have been fetched.
+    NotificationAdded(ResolvedNotification),
+    /// A notification was modified and its attributes have been fetched again.
+    NotificationModified(ResolvedNotification),
+    NotificationRemoved(Notification),
+    /// The response to a `fetch_notification_attributes` call.
+    NotificationAttributes(GetNotificationAttributesResponse),
+    /// The response to a `fetch_app_attributes` call.
+    AppAttributes(GetAppAttributesResponse),
+    /// A `perform_action` write was acknowledged by the iOS device.
+    ActionPerformed { notification_uid: u32, action_id: ActionID },
+    /// The attributes of an added or modified notification couldn't be fetched.
+    ResolveFailed { notification: Notification, error: Error },
+    /// The iOS device rejected a command queued by a `fetch_*` or `perform_action` call.
+    ///
+    /// `notification_uid` is set for every command but `GetAppAttributes`, which sets
+    /// `app_identifier` without its NULL terminator instead.
+    CommandFailed {
+        command_id: CommandID,
+        notification_uid: Option<u32>,
+        app_identifier: Option<String>,
+        error: Error,
+    },
+}
+
+/// The errors returned by the clients that drive a transport.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClientError<E> {
+    /// A packet couldn't be handled or the iOS device rejected a command.
+    Ancs(Error),
+    Transport(E),
+    /// The iOS device disconnected before the command completed.
+    Disconnected,
+}
+
+impl<E: fmt::Display> fmt::Display for ClientError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Ancs(error) => write!(f, "{}", error),
+            ClientError::Transport(error) => write!(f, "transport error: {}", error),
+            ClientError::Disconnected => write!(f, "device disconnected"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ClientError<E> {}
+
+impl<E> From<Error> for ClientError<E> {
+    fn from(original: Error) -> ClientError<E> {
+        ClientError::Ancs(original)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Command {
+    /// Fetches the configured attributes for a notification event before reporting it.
+    Resolve(Notification),
+    GetNotificationAttributes(GetNotificationAttributesRequest),
+    GetAppAttributes(GetAppAttributesRequest),
+    PerformNotificationAction(PerformNotificationActionRequest),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct InFlight {
+    command: Command,
+    reassembler: Option<DataSourceReassembler>,
+    /// Set when the outcome of the command is no longer wanted, such as when the notification
+    /// being resolved was removed, it still completes but isn't reported.
+    cancelled: bool,
+}
+
+/// The `AncsClient` type. See [the module level documentation](index.html) for more.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AncsClient {
+    attribute_ids: Vec<RequestedAttribute>,
+    queue: VecDeque<Command>,
+    in_flight: Option<InFlight>,
+    events: VecDeque<ClientEvent>,
+}
+
+impl Default for AncsClient {
+    fn default() -> AncsClient {
+        AncsClient::new()
+    }
+}
+
+impl AncsClient {
+    /// Creates a client that fetches the app identifier, title, message and date of
+    /// every added or modified notification.
+    pub fn new() -> AncsClient {
+        AncsClient {
+            attribute_ids: vec![
+                RequestedAttribute::AppIdentifier,
+                RequestedAttribute::Title(128),
+                RequestedAttribute::Message(512),
+                RequestedAttribute::Date,
+            ],
+            queue: VecDeque::new(),
+            in_flight: None,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Creates a client that fetches `attribute_ids` for every added or modified notification
+    ///
+    /// Fails with `Error::MaxLengthMismatch` if an attribute can't be requested, such as an
+    /// `Unknown` one holding the ID of a sized attribute.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::{NotificationAttributeID, RequestedAttribute};
+    /// # use ancs::client::AncsClient;
+    /// assert!(AncsClient::with_attributes(vec![RequestedAttribute::Title(64)]).is_ok());
+    /// assert_eq!(
+    ///     AncsClient::with_attributes(vec![RequestedAttribute::Unknown(3)]),
+    ///     Err(Error::MaxLengthMismatch(NotificationAttributeID::Message)),
+    /// );
+    /// ```
+    pub fn with_attributes(attribute_ids: Vec<RequestedAttribute>) -> Result<AncsClient, Error> {
+        for attribute in &attribute_ids {
+            check(attribute)?;
+        }
+
+        Ok(AncsClient {
+            attribute_ids,
+            ..AncsClient::new()
+        })
+    }
+
+    /// Handles bytes received on the Notification Source characteristic
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::client::{AncsClient, ClientEvent};
+    /// let mut client = AncsClient::new();
+    ///
+    /// // A new incoming call with UID 1 is reported on the Notification Source
+    /// client.handle_notification_source(&[0, 0b00011000, 1, 1, 1, 0, 0, 0]).unwrap();
+    ///
+    /// // The client asks for its attributes on the Control Point
+    /// let write = client.poll_transmit().unwrap();
+    /// assert_eq!(write, vec![0, 1, 0, 0, 0, 0, 1, 128, 0, 3, 0, 2, 5]);
+    /// assert_eq!(client.poll_transmit(), None);
+    ///
+    /// // iOS answers on the Data Source, possibly split over several notifications
+    /// client.handle_data_source(&[0, 1, 0, 0, 0, 0, 3, 0, 102, 111, 111]).unwrap();
+    /// client.handle_data_source(&[1, 3, 0, 66, 111, 98, 3, 0, 0, 5, 0, 0]).unwrap();
+    ///
+    /// match client.poll_event() {
+    ///     Some(ClientEvent::NotificationAdded(notification)) => {
+    ///         assert_eq!(notification.notification.notification_uid, 1);
+    ///         assert_eq!(notification.app_identifier(), Some("foo"));
+    ///         assert_eq!(notification.title(), Some("Bob"));
+    ///         assert_eq!(notification.message(), None);
+    ///     }
+    ///     _ => panic!("expected a resolved notification"),
+    /// }
+    /// ```
+    pub fn handle_notification_source(&mut self, data: &[u8]) -> Result<(), Error> {
+        let (_, notification) = Notification::parse(data)?;
+
+        match notification.event_id {
+            EventID::NotificationAdded | EventID::NotificationModified => {
+                self.queue_resolve(notification)
+            }
+            EventID::NotificationRemoved => {
+                let uid = notification.notification_uid;
+
+                self.queue.retain(|command| !matches!(command, Command::Resolve(queued) if queued.notification_uid == uid));
+
+                if let Some(in_flight) = self.in_flight.as_mut() {
+                    if matches!(&in_flight.command, Command::Resolve(resolving) if resolving.notification_uid == uid) {
+                        in_flight.cancelled = true;
+                    }
+                }
+
+                self.events.push_back(ClientEvent::NotificationRemoved(notification));
+            }
+            // Events added in later versions of ANCS can't be acted on, so they are ignored.
+            EventID::Unknown(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Handles bytes received on the Data Source characteristic.
+    ///
+    /// Returns `Error::UnsolicitedResponse` if no command is waiting for a response, any
+    /// other error aborts the command in flight so the next one can be sent and is also
+    /// reported as a `ClientEvent::CommandFailed` or `ClientEvent::ResolveFailed`.
+    pub fn handle_data_source(&mut self, data: &[u8]) -> Result<(), Error> {
+        let reassembler = match self.in_flight.as_mut().and_then(|in_flight| in_flight.reassembler.as_mut()) {
+            Some(reassembler) => reassembler,
+            None => return Err(Error::UnsolicitedResponse),
+        };
+
+        let response = match reassembler.push(data) {
+            Ok(Some(response)) => response,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                self.cancel_in_flight(e.clone());
+
+                return Err(e);
+            }
+        };
+
+        let in_flight = match self.in_flight.take() {
+            Some(in_flight) if !in_flight.cancelled => in_flight,
+            _ => return Ok(()),
+        };
+
+        match (in_flight.command, response) {
+            (Command::Resolve(notification), DataSourceResponse::GetNotificationAttributes(response)) => {
+                let resolved = ResolvedNotification {
+                    notification,
+                    attributes: response.attribute_list,
+                };
+
+                self.events.push_back(match resolved.notification.event_id {
+                    EventID::NotificationAdded => ClientEvent::NotificationAdded(resolved),
+                    _ => ClientEvent::NotificationModified(resolved),
+                });
+            }
+            (_, DataSourceResponse::GetNotificationAttributes(response)) => {
+                self.events.push_back(ClientEvent::NotificationAttributes(response));
+            }
+            (_, DataSourceResponse::GetAppAttributes(response)) => {
+                self.events.push_back(ClientEvent::AppAttributes(response));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Signals that the last Control Point write returned by `poll_transmit` was acknowledged.
+    ///
+    /// Performing an action has no Data Source response, so the acknowledgement is what
+    /// completes it. For every other command this is a no-op as they complete once their
+    /// response has been received.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::client::{AncsClient, ClientEvent};
+    /// let mut client = AncsClient::new();
+    ///
+    /// client.perform_action(1, ActionID::Negative);
+    /// assert_eq!(client.poll_transmit(), Some(vec![2, 1, 0, 0, 0, 1]));
+    ///
+    /// client.handle_write_complete();
+    /// assert_eq!(client.poll_event(), Some(ClientEvent::ActionPerformed { notification_uid: 1, action_id: ActionID::Negative }));
+    /// ```
+    pub fn handle_write_complete(&mut self) {
+        if let Some(InFlight { command: Command::PerformNotificationAction(request), cancelled, .. }) = &self.in_flight {
+            if !cancelled {
+                self.events.push_back(ClientEvent::ActionPerformed {
+                    notification_uid: request.notification_uid,
+                    action_id: request.action_id,
+                });
+            }
+
+            self.in_flight = None;
+        }
+    }
+
+    /// Signals that the last Control Point write returned by `poll_transmit` failed with
+    /// the ATT error code `att_error`.
+    ///
+    /// The command in flight is aborted and reported as a `ClientEvent::CommandFailed` or
+    /// `ClientEvent::ResolveFailed`, carrying an `Error::ControlPoint` for the errors defined by ANCS.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::attributes::command::CommandID;
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// # use ancs::client::{AncsClient, ClientEvent};
+    /// let mut client = AncsClient::new();
+    ///
+    /// client.perform_action(1, ActionID::Positive);
+    /// client.poll_transmit().unwrap();
+    /// client.handle_write_error(0xA3);
+    ///
+    /// assert_eq!(client.poll_event(), Some(ClientEvent::CommandFailed {
+    ///     command_id: CommandID::PerformNotificationAction,
+    ///     notification_uid: Some(1),
+    ///     app_identifier: None,
+    ///     error: Error::ControlPoint(AncsErrorCode::ActionFailed),
+    /// }));
+    /// assert!(!client.is_busy());
+    /// ```
+    pub fn handle_write_error(&mut self, att_error: u8) {
+        let error = match AncsErrorCode::try_from(att_error) {
+            Ok(code) => Error::from(code),
+            Err(e) => e,
+        };
+
+        self.cancel_in_flight(error);
+    }
+
+    /// Aborts the command in flight, reporting it as a `ClientEvent::CommandFailed` or
+    /// `ClientEvent::ResolveFailed` carrying `error`
+    ///
+    /// Used to give up on a command whose response or acknowledgement didn't arrive in time,
+    /// so the commands queued behind it can be sent. A response that still arrives afterwards
+    /// is rejected with `Error::UnsolicitedResponse`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::command::CommandID;
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::client::{AncsClient, ClientEvent};
+    /// let mut client = AncsClient::new();
+    ///
+    /// client.fetch_notification_attributes(7, vec![RequestedAttribute::Title(32)]).unwrap();
+    /// client.poll_transmit().unwrap();
+    ///
+    /// // The response didn't arrive before the deadline
+    /// client.cancel_in_flight(Error::Timeout);
+    ///
+    /// assert_eq!(client.poll_event(), Some(ClientEvent::CommandFailed {
+    ///     command_id: CommandID::GetNotificationAttributes,
+    ///     notification_uid: Some(7),
+    ///     app_identifier: None,
+    ///     error: Error::Timeout,
+    /// }));
+    /// assert!(!client.is_busy());
+    /// ```
+    pub fn cancel_in_flight(&mut self, error: Error) {
+        match self.in_flight.take() {
+            Some(in_flight) if !in_flight.cancelled => self.command_failed(in_flight.command, error),
+            _ => {}
+        }
+    }
+
+    /// Queues a request for the attributes of a notification, the response is reported
+    /// as a `ClientEvent::NotificationAttributes`
+    ///
+    /// Fails without queuing anything if the request can't be encoded, see `with_attributes`.
+    pub fn fetch_notification_attributes(
+        &mut self,
+        notification_uid: u32,
+        attribute_ids: Vec<RequestedAttribute>,
+    ) -> Result<(), Error> {
+        let request = GetNotificationAttributesRequest::new(notification_uid, attribute_ids);
+        check(&request)?;
+
+        self.queue.push_back(Command::GetNotificationAttributes(request));
+
+        Ok(())
+    }
+
+    /// Queues a request for the attributes of an app, the response is reported
+    /// as a `ClientEvent::AppAttributes`
+    ///
+    /// Fails without queuing anything if the request can't be encoded, such as with
+    /// `Error::EmptyAppIdentifier`.
+    pub fn fetch_app_attributes(&mut self, app_identifier: String, attribute_ids: Vec<AppAttributeID>) -> Result<(), Error> {
+        let request = GetAppAttributesRequest::new(app_identifier, attribute_ids);
+        check(&request)?;
+
+        self.queue.push_back(Command::GetAppAttributes(request));
+
+        Ok(())
+    }
+
+    /// Queues an action to perform on a notification, it is reported as a
+    /// `ClientEvent::ActionPerformed` once `handle_write_complete` is called.
+    pub fn perform_action(&mut self, notification_uid: u32, action_id: ActionID) {
+        self.queue.push_back(Command::PerformNotificationAction(PerformNotificationActionRequest::new(
+            notification_uid,
+            action_id,
+        )));
+    }
+
+    /// Returns the next write for the Control Point, if no command is in flight
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::client::AncsClient;
+    /// let mut client = AncsClient::new();
+    ///
+    /// client.handle_notification_source(&[0, 0, 4, 1, 7, 0, 0, 0]).unwrap();
+    /// client.perform_action(7, ActionID::Positive);
+    ///
+    /// // The action waits until the attributes of notification 7 have arrived
+    /// assert!(client.poll_transmit().is_some());
+    /// assert_eq!(client.poll_transmit(), None);
+    ///
+    /// client.handle_data_source(&[0, 7, 0, 0, 0, 0, 0, 0, 1, 0, 0, 3, 0, 0, 5, 0, 0]).unwrap();
+    /// assert_eq!(client.poll_transmit(), Some(vec![2, 7, 0, 0, 0, 0]));
+    /// ```
+    pub fn poll_transmit(&mut self) -> Option<Vec<u8>> {
+        while self.in_flight.is_none() {
+            let command = self.queue.pop_front()?;
+
+            let (data, reassembler) = match &command {
+                Command::Resolve(notification) => {
+                    let request =
+                        GetNotificationAttributesRequest::new(notification.notification_uid, self.attribute_ids.clone());
+
+                    (request.clone().try_into(), Some(DataSourceReassembler::from(&request)))
+                }
+                Command::GetNotificationAttributes(request) => {
+                    (request.clone().try_into(), Some(DataSourceReassembler::from(request)))
+                }
+                Command::GetAppAttributes(request) => {
+                    (request.clone().try_into(), Some(DataSourceReassembler::from(request)))
+                }
+                Command::PerformNotificationAction(request) => (Ok(request.clone().into()), None),
+            };
+
+            // Requests are checked when they are queued, one that still fails to encode can
+            // never succeed so it is reported and dropped rather than wedging the queue.
+            match data {
+                Ok(data) => {
+                    self.in_flight = Some(InFlight {
+                        command,
+                        reassembler,
+                        cancelled: false,
+                    });
+
+                    return Some(data);
+                }
+                Err(e) => self.command_failed(command, e),
+            }
+        }
+
+        None
+    }
+
+    /// Returns the next event produced by the client.
+    pub fn poll_event(&mut self) -> Option<ClientEvent> {
+        self.events.pop_front()
+    }
+
+    /// Determines if a command is waiting for its response or acknowledgement.
+    pub fn is_busy(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Returns the request written to the Control Point for the command in flight.
+    #[cfg(feature = "std")]
+    fn in_flight_request(&self) -> Option<ControlPointRequest> {
+        let request = match &self.in_flight.as_ref()?.command {
+            Command::Resolve(notification) => {
+                GetNotificationAttributesRequest::new(notification.notification_uid, self.attribute_ids.clone()).into()
+            }
+            Command::GetNotificationAttributes(request) => request.clone().into(),
+            Command::GetAppAttributes(request) => request.clone().into(),
+            Command::PerformNotificationAction(request) => request.clone().into(),
+        };
+
+        Some(request)
+    }
+
+    /// Drops the first queued command matching `issued`, or stops reporting the command in
+    /// flight if it matches instead, so a command nobody waits on anymore doesn't produce an event.
+    #[cfg(feature = "std")]
+    fn cancel(&mut self, issued: impl Fn(&Command) -> bool) {
+        if let Some(index) = self.queue.iter().position(&issued) {
+            self.queue.remove(index);
+        } else if let Some(in_flight) = self.in_flight.as_mut().filter(|in_flight| issued(&in_flight.command)) {
+            in_flight.cancelled = true;
+        }
+    }
+
+    fn command_failed(&mut self, command: Command, error: Error) {
+        let (command_id, notification_uid, app_identifier) = match command {
+            Command::Resolve(notification) => {
+                self.events.push_back(ClientEvent::ResolveFailed { notification, error });
+                return;
+            }
+            Command::GetNotificationAttributes(request) => {
+                (CommandID::GetNotificationAttributes, Some(request.notification_uid), None)
+            }
+            Command::GetAppAttributes(request) => {
+                (CommandID::GetAppAttributes, None, Some(request.app_identifier.trim_end_matches('\0').into()))
+            }
+            Command::PerformNotificationAction(request) => {
+                (CommandID::PerformNotificationAction, Some(request.notification_uid), None)
+            }
+        };
+
+        self.events.push_back(ClientEvent::CommandFailed {
+            command_id,
+            notification_uid,
+            app_identifier,
+            error,
+        });
+    }
+
+    fn queue_resolve(&mut self, notification: Notification) {
+        let uid = notification.notification_uid;
+        let queued = self.queue.iter_mut().find_map(|command| match command {
+            Command::Resolve(queued) if queued.notification_uid == uid => Some(queued),
+            _ => None,
+        });
+
+        // A notification that hasn't been fetched yet only needs its latest state, the
+        // event it will be reported as stays the same so an unreported add isn't lost.
+        match queued {
+            Some(queued) => {
+                *queued = Notification {
+                    event_id: queued.event_id,
+                    ..notification
+                }
+            }
+            None => self.queue.push_back(Command::Resolve(notification)),
+        }
+    }
+}
+
+/// Checks that a request or attribute can be encoded, so it can't fail once it is sent.
+fn check<T: Encode + ?Sized>(value: &T) -> Result<(), Error> {
+    to_vec(value).map(drop)
+}
@@ -0,0 +1,270 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::pin::pin;
+use std::time::Duration;
+
+use futures_timer::Delay;
+use futures_util::future::{select, Either};
+use futures_util::stream::{self, Stream};
+
+use crate::attributes::action::ActionID;
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::notification::RequestedAttribute;
+use crate::characteristics::data_source::{GetAppAttributesResponse, GetNotificationAttributesResponse};
+use crate::client::driver::{self, Awaited, Driver};
+use crate::client::{AncsClient, ClientError, ClientEvent};
+use crate::transport::{AncsTransport, Packet};
+
+/// An `AncsClient` driving an `AncsTransport`.
+///
+/// Notification events are read from `notifications` or `next_event`, while commands are
+/// awaited directly. Events that arrive while a command is awaited are kept until they
+/// are read.
+///
+/// A command whose response or acknowledgement doesn't arrive within the timeout fails with
+/// `Error::Timeout`. Dropping the future of a command before it completed abandons it, the
+/// client won't report its outcome later.
+///
+/// # Examples
+#[cfg_attr(feature = "tokio", doc = "```")]
+#[cfg_attr(not(feature = "tokio"), doc = "```ignore")]
+/// # use futures_util::StreamExt;
+/// # use ancs::attributes::notification::RequestedAttribute;
+/// # use ancs::client::{AsyncAncsClient, ClientEvent};
+/// # use ancs::transport::memory;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (transport, mut device) = memory::pair();
+/// let mut client = AsyncAncsClient::new(transport);
+/// client.subscribe().await.unwrap();
+///
+/// // A new incoming call with UID 1 and the attributes the client will fetch for it
+/// device.notify_notification_source(vec![0, 0b00011000, 1, 1, 1, 0, 0, 0]).unwrap();
+/// device.notify_data_source(vec![0, 1, 0, 0, 0, 0, 3, 0, 102, 111, 111, 1, 3, 0, 66, 111, 98, 3, 0, 0, 5, 0, 0]).unwrap();
+///
+/// match client.notifications().next().await {
+///     Some(Ok(ClientEvent::NotificationAdded(notification))) => assert_eq!(notification.title(), Some("Bob")),
+///     _ => panic!("expected a resolved notification"),
+/// }
+/// assert_eq!(device.next_write().await, Some(vec![0, 1, 0, 0, 0, 0, 1, 128, 0, 3, 0, 2, 5]));
+///
+/// device.notify_data_source(vec![0, 1, 0, 0, 0, 3, 5, 0, 72, 101, 108, 108, 111]).unwrap();
+/// let response = client.fetch_attributes(1, vec![RequestedAttribute::Message(32)]).await.unwrap();
+///
+/// assert_eq!(response.attribute_list[0].value, Some("Hello".into()));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncAncsClient<T> {
+    transport: T,
+    driver: Driver,
+}
+
+impl<T: AncsTransport> AsyncAncsClient<T> {
+    /// Creates a client that fetches the attributes `AncsClient::new` does for every notification.
+    pub fn new(transport: T) -> AsyncAncsClient<T> {
+        AsyncAncsClient::with_client(transport, AncsClient::new())
+    }
+
+    /// Creates a client driving an existing `AncsClient`, such as one created with `AncsClient::with_attributes`.
+    pub fn with_client(transport: T, client: AncsClient) -> AsyncAncsClient<T> {
+        AsyncAncsClient {
+            transport,
+            driver: Driver::new(client),
+        }
+    }
+
+    /// Sets how long a command may wait for its response or acknowledgement before it fails
+    /// with `Error::Timeout`, which is 10 seconds unless changed
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "tokio", doc = "```")]
+    #[cfg_attr(not(feature = "tokio"), doc = "```ignore")]
+    /// # use std::time::Duration;
+    /// # use futures_util::FutureExt;
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::client::{AsyncAncsClient, ClientError};
+    /// # use ancs::transport::memory;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let (transport, device) = memory::pair();
+    /// let mut client = AsyncAncsClient::new(transport);
+    /// client.set_timeout(Duration::from_millis(10));
+    ///
+    /// assert_eq!(
+    ///     client.fetch_attributes(1, vec![RequestedAttribute::Title(32)]).await,
+    ///     Err(ClientError::Ancs(Error::Timeout)),
+    /// );
+    ///
+    /// // A fetch whose future is dropped is abandoned, its late response is ignored
+    /// assert!(client.fetch_attributes(2, vec![RequestedAttribute::Title(32)]).now_or_never().is_none());
+    /// device.notify_data_source(vec![0, 2, 0, 0, 0, 1, 0, 0]).unwrap();
+    /// device.notify_data_source(vec![0, 3, 0, 0, 0, 1, 0, 0]).unwrap();
+    ///
+    /// assert!(client.fetch_attributes(3, vec![RequestedAttribute::Title(32)]).await.is_ok());
+    ///
+    /// drop(device);
+    /// assert!(client.next_event().await.is_none());
+    /// # }
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.driver.set_timeout(timeout);
+    }
+
+    /// Subscribes to the Notification Source and Data Source through the transport.
+    pub async fn subscribe(&mut self) -> Result<(), ClientError<T::Error>> {
+        self.transport.subscribe().await.map_err(ClientError::Transport)
+    }
+
+    /// Waits for the next event, returning `None` once the iOS device has disconnected.
+    ///
+    /// Packets that can't be handled are returned as an `Err` without ending the events.
+    pub async fn next_event(&mut self) -> Option<Result<ClientEvent, ClientError<T::Error>>> {
+        loop {
+            self.driver.expire();
+
+            if let Err(e) = transmit(&mut self.transport, &mut self.driver).await {
+                return Some(Err(e));
+            }
+
+            if let Some(event) = self.driver.next_event() {
+                return Some(event.map_err(ClientError::Ancs));
+            }
+
+            let Some(received) = receive(&mut self.transport, self.driver.next_timeout()).await else {
+                continue;
+            };
+
+            match received {
+                Ok(Some(packet)) => self.driver.handle_packet(packet),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(ClientError::Transport(e))),
+            }
+        }
+    }
+
+    /// Returns a `Stream` of the events returned by `next_event`, which includes every
+    /// added and modified notification along with its attributes.
+    pub fn notifications(&mut self) -> impl Stream<Item = Result<ClientEvent, ClientError<T::Error>>> + Unpin + '_ {
+        Box::pin(stream::unfold(self, |client| async move {
+            let event = client.next_event().await?;
+            Some((event, client))
+        }))
+    }
+
+    /// Fetches attributes of a notification.
+    pub async fn fetch_attributes(
+        &mut self,
+        notification_uid: u32,
+        attribute_ids: Vec<RequestedAttribute>,
+    ) -> Result<GetNotificationAttributesResponse, ClientError<T::Error>> {
+        let awaited = self.driver.fetch_notification_attributes(notification_uid, attribute_ids)?;
+        let event = self.wait_for(awaited).await?;
+
+        Ok(driver::notification_attributes(event)?)
+    }
+
+    /// Fetches attributes of an app.
+    pub async fn fetch_app_attributes(
+        &mut self,
+        app_identifier: String,
+        attribute_ids: Vec<AppAttributeID>,
+    ) -> Result<GetAppAttributesResponse, ClientError<T::Error>> {
+        let awaited = self.driver.fetch_app_attributes(app_identifier, attribute_ids)?;
+        let event = self.wait_for(awaited).await?;
+
+        Ok(driver::app_attributes(event)?)
+    }
+
+    /// Performs an action on a notification, completing once the iOS device acknowledged it
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "tokio", doc = "```")]
+    #[cfg_attr(not(feature = "tokio"), doc = "```ignore")]
+    /// # use ancs::Error;
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// # use ancs::client::{AsyncAncsClient, ClientError};
+    /// # use ancs::transport::memory;
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let (transport, mut device) = memory::pair();
+    /// let mut client = AsyncAncsClient::new(transport);
+    ///
+    /// client.perform_action(7, ActionID::Positive).await.unwrap();
+    /// assert_eq!(device.next_write().await, Some(vec![2, 7, 0, 0, 0, 0]));
+    ///
+    /// device.reject_next_write(0xA3);
+    /// assert_eq!(
+    ///     client.perform_action(7, ActionID::Negative).await,
+    ///     Err(ClientError::Ancs(Error::ControlPoint(AncsErrorCode::ActionFailed))),
+    /// );
+    /// # }
+    /// ```
+    pub async fn perform_action(&mut self, notification_uid: u32, action_id: ActionID) -> Result<(), ClientError<T::Error>> {
+        let awaited = self.driver.perform_action(notification_uid, action_id);
+        let event = self.wait_for(awaited).await?;
+
+        Ok(driver::action(event)?)
+    }
+
+    /// Returns the transport, dropping any events that weren't read.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Handles packets until an event completing `awaited` is produced and returns it.
+    ///
+    /// `awaited` is abandoned if this returns an error or is dropped before it completed.
+    async fn wait_for(&mut self, awaited: Awaited) -> Result<ClientEvent, ClientError<T::Error>> {
+        let mut waiting = self.driver.wait(awaited);
+
+        loop {
+            waiting.expire();
+            transmit(&mut self.transport, &mut waiting).await?;
+
+            if let Some(event) = waiting.take() {
+                return Ok(event);
+            }
+
+            let Some(received) = receive(&mut self.transport, waiting.next_timeout()).await else {
+                continue;
+            };
+
+            match received.map_err(ClientError::Transport)? {
+                Some(packet) => waiting.handle_packet(packet),
+                None => return Err(ClientError::Disconnected),
+            }
+        }
+    }
+}
+
+/// Writes every queued Control Point request the client hands out.
+async fn transmit<T: AncsTransport>(transport: &mut T, driver: &mut Driver) -> Result<(), ClientError<T::Error>> {
+    while let Some(data) = driver.poll_transmit() {
+        let result = transport.write_control_point(data).await;
+        driver.handle_write(result)?;
+    }
+
+    Ok(())
+}
+
+/// Receives the next packet, giving up with `None` once `timeout` passed so the command
+/// in flight can be expired. This drops the receive future, which `AncsTransport::receive`
+/// requires to be cancel-safe.
+async fn receive<T: AncsTransport>(
+    transport: &mut T,
+    timeout: Option<Duration>,
+) -> Option<Result<Option<Packet>, T::Error>> {
+    let timeout = match timeout {
+        Some(timeout) => Delay::new(timeout),
+        None => return Some(transport.receive().await),
+    };
+
+    match select(pin!(transport.receive()), timeout).await {
+        Either::Left((received, _)) => Some(received),
+        Either::Right(_) => None,
+    }
+}
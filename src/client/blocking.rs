@@ -0,0 +1,282 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter;
+use std::time::Duration;
+
+use crate::attributes::action::ActionID;
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::notification::RequestedAttribute;
+use crate::characteristics::data_source::{GetAppAttributesResponse, GetNotificationAttributesResponse};
+use crate::client::driver::{self, Awaited, Driver};
+use crate::client::{AncsClient, ClientError, ClientEvent};
+use crate::transport::{BlockingTransport, Received};
+
+/// An `AncsClient` driving a `BlockingTransport`.
+///
+/// The blocking counterpart of `AsyncAncsClient`, it handles packets and commands the
+/// same way but waits on the transport by blocking the calling thread. Receiving is given
+/// the time left until the command in flight expires, so a lost response fails the command
+/// with `Error::Timeout` rather than blocking forever.
+///
+/// # Examples
+/// ```
+/// # use std::collections::VecDeque;
+/// # use ancs::attributes::notification::RequestedAttribute;
+/// # use ancs::client::{ClientEvent, SyncAncsClient};
+/// # use std::time::Duration;
+/// # use ancs::transport::{BlockingTransport, Packet, Received, WriteError};
+/// /// Replays the packets of a recorded session.
+/// struct Replay {
+///     packets: VecDeque<Packet>,
+///     writes: Vec<Vec<u8>>,
+/// }
+///
+/// impl BlockingTransport for Replay {
+///     type Error = ();
+///
+///     fn subscribe(&mut self) -> Result<(), ()> {
+///         Ok(())
+///     }
+///
+///     fn receive(&mut self, _: Option<Duration>) -> Result<Received, ()> {
+///         Ok(self.packets.pop_front().map_or(Received::Disconnected, Received::Packet))
+///     }
+///
+///     fn write_control_point(&mut self, data: Vec<u8>) -> Result<(), WriteError<()>> {
+///         self.writes.push(data);
+///         Ok(())
+///     }
+/// }
+///
+/// let transport = Replay {
+///     packets: VecDeque::from([
+///         // A new incoming call with UID 1 and the attributes the client will fetch for it
+///         Packet::NotificationSource(vec![0, 0b00011000, 1, 1, 1, 0, 0, 0]),
+///         Packet::DataSource(vec![0, 1, 0, 0, 0, 0, 3, 0, 102, 111, 111, 1, 3, 0, 66, 111, 98, 3, 0, 0, 5, 0, 0]),
+///         Packet::DataSource(vec![0, 1, 0, 0, 0, 3, 5, 0, 72, 101, 108, 108, 111]),
+///     ]),
+///     writes: Vec::new(),
+/// };
+/// let mut client = SyncAncsClient::new(transport);
+/// client.subscribe().unwrap();
+///
+/// match client.next_event() {
+///     Some(Ok(ClientEvent::NotificationAdded(notification))) => assert_eq!(notification.title(), Some("Bob")),
+///     _ => panic!("expected a resolved notification"),
+/// }
+///
+/// let response = client.fetch_notification(1, vec![RequestedAttribute::Message(32)]).unwrap();
+/// assert_eq!(response.attribute_list[0].value, Some("Hello".into()));
+///
+/// assert_eq!(client.into_inner().writes.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct SyncAncsClient<T> {
+    transport: T,
+    driver: Driver,
+}
+
+impl<T: BlockingTransport> SyncAncsClient<T> {
+    /// Creates a client that fetches the attributes `AncsClient::new` does for every notification.
+    pub fn new(transport: T) -> SyncAncsClient<T> {
+        SyncAncsClient::with_client(transport, AncsClient::new())
+    }
+
+    /// Creates a client driving an existing `AncsClient`, such as one created with `AncsClient::with_attributes`.
+    pub fn with_client(transport: T, client: AncsClient) -> SyncAncsClient<T> {
+        SyncAncsClient {
+            transport,
+            driver: Driver::new(client),
+        }
+    }
+
+    /// Sets how long a command may wait for its response or acknowledgement before it fails
+    /// with `Error::Timeout`, which is 10 seconds unless changed
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::thread;
+    /// # use std::time::Duration;
+    /// # use ancs::Error;
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::client::{ClientError, SyncAncsClient};
+    /// # use ancs::transport::{BlockingTransport, Received, WriteError};
+    /// /// Acknowledges every Control Point write but never notifies anything.
+    /// struct Silent;
+    ///
+    /// impl BlockingTransport for Silent {
+    ///     type Error = ();
+    /// #
+    /// #   fn subscribe(&mut self) -> Result<(), ()> {
+    /// #       Ok(())
+    /// #   }
+    ///
+    ///     fn receive(&mut self, timeout: Option<Duration>) -> Result<Received, ()> {
+    ///         match timeout {
+    ///             Some(timeout) => {
+    ///                 thread::sleep(timeout);
+    ///                 Ok(Received::TimedOut)
+    ///             }
+    ///             None => Ok(Received::Disconnected),
+    ///         }
+    ///     }
+    /// #
+    /// #   fn write_control_point(&mut self, _: Vec<u8>) -> Result<(), WriteError<()>> {
+    /// #       Ok(())
+    /// #   }
+    /// }
+    ///
+    /// let mut client = SyncAncsClient::new(Silent);
+    /// client.set_timeout(Duration::from_millis(10));
+    ///
+    /// assert_eq!(
+    ///     client.fetch_notification(1, vec![RequestedAttribute::Title(32)]),
+    ///     Err(ClientError::Ancs(Error::Timeout)),
+    /// );
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.driver.set_timeout(timeout);
+    }
+
+    /// Subscribes to the Notification Source and Data Source through the transport.
+    pub fn subscribe(&mut self) -> Result<(), ClientError<T::Error>> {
+        self.transport.subscribe().map_err(ClientError::Transport)
+    }
+
+    /// Blocks until the next event, returning `None` once the iOS device has disconnected.
+    ///
+    /// Packets that can't be handled are returned as an `Err` without ending the events.
+    pub fn next_event(&mut self) -> Option<Result<ClientEvent, ClientError<T::Error>>> {
+        loop {
+            self.driver.expire();
+
+            if let Err(e) = transmit(&mut self.transport, &mut self.driver) {
+                return Some(Err(e));
+            }
+
+            if let Some(event) = self.driver.next_event() {
+                return Some(event.map_err(ClientError::Ancs));
+            }
+
+            match self.transport.receive(self.driver.next_timeout()) {
+                Ok(Received::Packet(packet)) => self.driver.handle_packet(packet),
+                // The command in flight expired, `expire` aborts it on the next iteration.
+                Ok(Received::TimedOut) => {}
+                Ok(Received::Disconnected) => return None,
+                Err(e) => return Some(Err(ClientError::Transport(e))),
+            }
+        }
+    }
+
+    /// Returns an `Iterator` over the events returned by `next_event`, which includes every
+    /// added and modified notification along with its attributes.
+    pub fn notifications(&mut self) -> impl Iterator<Item = Result<ClientEvent, ClientError<T::Error>>> + '_ {
+        iter::from_fn(move || self.next_event())
+    }
+
+    /// Fetches attributes of a notification.
+    pub fn fetch_notification(
+        &mut self,
+        notification_uid: u32,
+        attribute_ids: Vec<RequestedAttribute>,
+    ) -> Result<GetNotificationAttributesResponse, ClientError<T::Error>> {
+        let awaited = self.driver.fetch_notification_attributes(notification_uid, attribute_ids)?;
+        let event = self.wait_for(awaited)?;
+
+        Ok(driver::notification_attributes(event)?)
+    }
+
+    /// Fetches attributes of an app.
+    pub fn fetch_app_attributes(
+        &mut self,
+        app_identifier: String,
+        attribute_ids: Vec<AppAttributeID>,
+    ) -> Result<GetAppAttributesResponse, ClientError<T::Error>> {
+        let awaited = self.driver.fetch_app_attributes(app_identifier, attribute_ids)?;
+        let event = self.wait_for(awaited)?;
+
+        Ok(driver::app_attributes(event)?)
+    }
+
+    /// Performs an action on a notification, returning once the iOS device acknowledged it
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// # use ancs::client::{ClientError, SyncAncsClient};
+    /// # use std::time::Duration;
+    /// # use ancs::transport::{BlockingTransport, Received, WriteError};
+    /// /// Rejects every Control Point write with an ATT error code.
+    /// struct Rejecting(u8);
+    ///
+    /// impl BlockingTransport for Rejecting {
+    ///     type Error = ();
+    /// #
+    /// #   fn subscribe(&mut self) -> Result<(), ()> {
+    /// #       Ok(())
+    /// #   }
+    /// #
+    /// #   fn receive(&mut self, _: Option<Duration>) -> Result<Received, ()> {
+    /// #       Ok(Received::Disconnected)
+    /// #   }
+    ///
+    ///     fn write_control_point(&mut self, _: Vec<u8>) -> Result<(), WriteError<()>> {
+    ///         Err(WriteError::Att(self.0))
+    ///     }
+    /// }
+    ///
+    /// let mut client = SyncAncsClient::new(Rejecting(0xA3));
+    ///
+    /// assert_eq!(
+    ///     client.perform_action(7, ActionID::Positive),
+    ///     Err(ClientError::Ancs(Error::ControlPoint(AncsErrorCode::ActionFailed))),
+    /// );
+    /// ```
+    pub fn perform_action(&mut self, notification_uid: u32, action_id: ActionID) -> Result<(), ClientError<T::Error>> {
+        let awaited = self.driver.perform_action(notification_uid, action_id);
+        let event = self.wait_for(awaited)?;
+
+        Ok(driver::action(event)?)
+    }
+
+    /// Returns the transport, dropping any events that weren't read.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Handles packets until an event completing `awaited` is produced and returns it.
+    ///
+    /// `awaited` is abandoned if this returns an error.
+    fn wait_for(&mut self, awaited: Awaited) -> Result<ClientEvent, ClientError<T::Error>> {
+        let mut waiting = self.driver.wait(awaited);
+
+        loop {
+            waiting.expire();
+            transmit(&mut self.transport, &mut waiting)?;
+
+            if let Some(event) = waiting.take() {
+                return Ok(event);
+            }
+
+            let timeout = waiting.next_timeout();
+
+            match self.transport.receive(timeout).map_err(ClientError::Transport)? {
+                Received::Packet(packet) => waiting.handle_packet(packet),
+                Received::TimedOut => {}
+                Received::Disconnected => return Err(ClientError::Disconnected),
+            }
+        }
+    }
+}
+
+/// Writes every queued Control Point request the client hands out.
+fn transmit<T: BlockingTransport>(transport: &mut T, driver: &mut Driver) -> Result<(), ClientError<T::Error>> {
+    while let Some(data) = driver.poll_transmit() {
+        let result = transport.write_control_point(data);
+        driver.handle_write(result)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use crate::attributes::action::ActionID;
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::command::CommandID;
+use crate::attributes::notification::RequestedAttribute;
+use crate::characteristics::data_source::{GetAppAttributesResponse, GetNotificationAttributesResponse};
+use crate::client::{AncsClient, ClientError, ClientEvent, Command};
+use crate::pending::{Clock, PendingCommands, SystemClock};
+use crate::transport::{Packet, WriteError};
+use crate::Error;
+
+/// How long a command may wait for its response or acknowledgement unless changed with `set_timeout`.
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A command a client is waiting on.
+///
+/// Only commands queued through the driver are awaited, the client reports the outcome of
+/// fetching the attributes of a notification event as a different event than the one of a
+/// `fetch_notification_attributes` call for the same notification, so they can't be confused.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum Awaited {
+    NotificationAttributes(u32),
+    /// Holds the app identifier without its NULL terminator.
+    AppAttributes(String),
+    Action(u32, ActionID),
+}
+
+impl Awaited {
+    /// Determines if `command` was queued for the awaited command.
+    fn issued(&self, command: &Command) -> bool {
+        match (self, command) {
+            (Awaited::NotificationAttributes(uid), Command::GetNotificationAttributes(request)) => {
+                request.notification_uid == *uid
+            }
+            (Awaited::AppAttributes(app_identifier), Command::GetAppAttributes(request)) => {
+                request.app_identifier.trim_end_matches('\0') == app_identifier
+            }
+            (Awaited::Action(uid, action_id), Command::PerformNotificationAction(request)) => {
+                request.notification_uid == *uid && request.action_id == *action_id
+            }
+            _ => false,
+        }
+    }
+
+    /// Determines if `event` completes the command.
+    fn completed_by(&self, event: &ClientEvent) -> bool {
+        match (self, event) {
+            (Awaited::NotificationAttributes(uid), ClientEvent::NotificationAttributes(response)) => {
+                response.notification_uid == *uid
+            }
+            (
+                Awaited::NotificationAttributes(uid),
+                ClientEvent::CommandFailed { command_id: CommandID::GetNotificationAttributes, notification_uid, .. },
+            ) => *notification_uid == Some(*uid),
+            (Awaited::AppAttributes(app_identifier), ClientEvent::AppAttributes(response)) => {
+                response.app_identifier.trim_end_matches('\0') == app_identifier
+            }
+            (
+                Awaited::AppAttributes(app_identifier),
+                ClientEvent::CommandFailed { command_id: CommandID::GetAppAttributes, app_identifier: failed, .. },
+            ) => failed.as_ref() == Some(app_identifier),
+            (Awaited::Action(uid, action_id), ClientEvent::ActionPerformed { notification_uid, action_id: id }) => {
+                notification_uid == uid && id == action_id
+            }
+            (
+                Awaited::Action(uid, _),
+                ClientEvent::CommandFailed { command_id: CommandID::PerformNotificationAction, notification_uid, .. },
+            ) => *notification_uid == Some(*uid),
+            _ => false,
+        }
+    }
+}
+
+/// The transport independent half of `AsyncAncsClient` and `SyncAncsClient`, which only
+/// differ in how they wait on their transport.
+///
+/// Events produced while a command is awaited are kept until they are read. The command in
+/// flight is tracked in a `PendingCommands` and aborted with `Error::Timeout` once `expire`
+/// finds its deadline has passed.
+#[derive(Debug)]
+pub(crate) struct Driver {
+    client: AncsClient,
+    events: VecDeque<Result<ClientEvent, Error>>,
+    pending: PendingCommands<SystemClock>,
+    timeout: Duration,
+}
+
+impl Driver {
+    pub(crate) fn new(client: AncsClient) -> Driver {
+        Driver {
+            client,
+            events: VecDeque::new(),
+            pending: PendingCommands::new(SystemClock),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets how long commands written from now on may wait for their response or acknowledgement.
+    pub(crate) fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub(crate) fn fetch_notification_attributes(
+        &mut self,
+        notification_uid: u32,
+        attribute_ids: Vec<RequestedAttribute>,
+    ) -> Result<Awaited, Error> {
+        self.client.fetch_notification_attributes(notification_uid, attribute_ids)?;
+
+        Ok(Awaited::NotificationAttributes(notification_uid))
+    }
+
+    pub(crate) fn fetch_app_attributes(
+        &mut self,
+        app_identifier: String,
+        attribute_ids: Vec<AppAttributeID>,
+    ) -> Result<Awaited, Error> {
+        let awaited = Awaited::AppAttributes(app_identifier.trim_end_matches('\0').into());
+        self.client.fetch_app_attributes(app_identifier, attribute_ids)?;
+
+        Ok(awaited)
+    }
+
+    pub(crate) fn perform_action(&mut self, notification_uid: u32, action_id: ActionID) -> Awaited {
+        self.client.perform_action(notification_uid, action_id);
+
+        Awaited::Action(notification_uid, action_id)
+    }
+
+    pub(crate) fn poll_transmit(&mut self) -> Option<Vec<u8>> {
+        let data = self.client.poll_transmit()?;
+
+        if let Some(request) = self.client.in_flight_request() {
+            self.pending.insert(request, SystemClock.now() + self.timeout);
+        }
+
+        Some(data)
+    }
+
+    /// Returns how long until the command in flight expires, which is when `expire` should
+    /// next be called.
+    pub(crate) fn next_timeout(&self) -> Option<Duration> {
+        let deadline = self.pending.next_deadline()?;
+
+        Some(deadline.saturating_duration_since(SystemClock.now()))
+    }
+
+    /// Aborts the command in flight if its deadline has passed.
+    pub(crate) fn expire(&mut self) {
+        if !self.pending.expire().is_empty() {
+            self.client.cancel_in_flight(Error::Timeout);
+            self.drain();
+        }
+    }
+
+    /// Handles the outcome of a Control Point write returned by `poll_transmit`
+    ///
+    /// A write the transport failed to make aborts the command with `Error::WriteFailed`,
+    /// so the client isn't left waiting for an answer to a request that was never sent.
+    pub(crate) fn handle_write<E>(&mut self, result: Result<(), WriteError<E>>) -> Result<(), ClientError<E>> {
+        match result {
+            Ok(()) => self.client.handle_write_complete(),
+            Err(WriteError::Att(att_error)) => self.client.handle_write_error(att_error),
+            Err(WriteError::Transport(e)) => {
+                self.client.cancel_in_flight(Error::WriteFailed);
+                self.drain();
+
+                return Err(ClientError::Transport(e));
+            }
+        }
+
+        self.drain();
+        Ok(())
+    }
+
+    /// Handles a packet, a packet that can't be handled is kept as an `Err` event.
+    pub(crate) fn handle_packet(&mut self, packet: Packet) {
+        let result = match packet {
+            Packet::NotificationSource(data) => self.client.handle_notification_source(&data),
+            Packet::DataSource(data) => self.client.handle_data_source(&data),
+        };
+
+        if let Err(e) = result {
+            self.events.push_back(Err(e));
+        }
+
+        self.drain();
+    }
+
+    pub(crate) fn next_event(&mut self) -> Option<Result<ClientEvent, Error>> {
+        self.events.pop_front()
+    }
+
+    /// Starts waiting on `awaited`, which is abandoned if the returned `Waiting` is dropped
+    /// before it completed.
+    pub(crate) fn wait(&mut self, awaited: Awaited) -> Waiting<'_> {
+        Waiting {
+            driver: self,
+            awaited: Some(awaited),
+        }
+    }
+
+    /// Removes and returns the oldest event completing `awaited`.
+    fn take(&mut self, awaited: &Awaited) -> Option<ClientEvent> {
+        let index = self
+            .events
+            .iter()
+            .position(|event| matches!(event, Ok(event) if awaited.completed_by(event)))?;
+
+        self.events.remove(index)?.ok()
+    }
+
+    /// Forgets a command nobody waits on anymore, dropping its outcome if it already
+    /// completed and keeping the client from reporting it otherwise.
+    fn abandon(&mut self, awaited: &Awaited) {
+        if self.take(awaited).is_none() {
+            self.client.cancel(|command| awaited.issued(command));
+        }
+    }
+
+    fn drain(&mut self) {
+        while let Some(event) = self.client.poll_event() {
+            self.events.push_back(Ok(event));
+        }
+
+        // Only a single command is ever in flight, once it completed nothing is pending.
+        if !self.client.is_busy() {
+            self.pending.clear();
+        }
+    }
+}
+
+/// A command being waited on, see `Driver::wait`.
+///
+/// Dereferences to the `Driver` so the transport can be driven while waiting. Dropping it
+/// before `take` returned the outcome, because the wait failed or its future was dropped,
+/// abandons the command.
+#[derive(Debug)]
+pub(crate) struct Waiting<'a> {
+    driver: &'a mut Driver,
+    awaited: Option<Awaited>,
+}
+
+impl Waiting<'_> {
+    /// Removes and returns the event completing the awaited command, if it has been produced.
+    pub(crate) fn take(&mut self) -> Option<ClientEvent> {
+        let event = self.driver.take(self.awaited.as_ref()?)?;
+        self.awaited = None;
+
+        Some(event)
+    }
+}
+
+impl Deref for Waiting<'_> {
+    type Target = Driver;
+
+    fn deref(&self) -> &Driver {
+        self.driver
+    }
+}
+
+impl DerefMut for Waiting<'_> {
+    fn deref_mut(&mut self) -> &mut Driver {
+        self.driver
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        if let Some(awaited) = self.awaited.take() {
+            self.driver.abandon(&awaited);
+        }
+    }
+}
+
+/// Returns the response of an `Awaited::NotificationAttributes` command.
+pub(crate) fn notification_attributes(event: ClientEvent) -> Result<GetNotificationAttributesResponse, Error> {
+    match event {
+        ClientEvent::NotificationAttributes(response) => Ok(response),
+        ClientEvent::CommandFailed { error, .. } => Err(error),
+        _ => unreachable!("only completing events are taken"),
+    }
+}
+
+/// Returns the response of an `Awaited::AppAttributes` command.
+pub(crate) fn app_attributes(event: ClientEvent) -> Result<GetAppAttributesResponse, Error> {
+    match event {
+        ClientEvent::AppAttributes(response) => Ok(response),
+        ClientEvent::CommandFailed { error, .. } => Err(error),
+        _ => unreachable!("only completing events are taken"),
+    }
+}
+
+/// Returns the result of an `Awaited::Action` command.
+pub(crate) fn action(event: ClientEvent) -> Result<(), Error> {
+    match event {
+        ClientEvent::CommandFailed { error, .. } => Err(error),
+        _ => Ok(()),
+    }
+}
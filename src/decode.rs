@@ -0,0 +1,96 @@
+//! ## Decoding
+//!
+//! Every packet and attribute type implements [`Decode`], which gives generic code such as
+//! codecs, reassemblers and fuzzers a single way to parse any ANCS wire type. The `parse`
+//! functions found throughout the crate are shorthands for [`Decode::decode`].
+//!
+//! Decoding can run in one of two modes. `decode` expects the whole packet and reports a
+//! short buffer as `Error::Truncated`, while `decode_streaming` reports it as a
+//! `nom::Err::Incomplete` carrying the number of bytes still needed, so the caller can
+//! wait for more data and try again.
+//!
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use nom::IResult;
+
+use crate::Error;
+
+/// A type that can be decoded from ANCS wire data.
+///
+/// The lifetime lets types such as `NotificationAttributeRef` borrow from the input,
+/// types that own their data implement `Decode<'a>` for every `'a`.
+pub trait Decode<'a>: Sized {
+    /// Decodes a value from the start of `i`, which may end part way through it
+    ///
+    /// Fails with `nom::Err::Incomplete` if `i` ends part way through a field, with
+    /// `Needed::Size` holding the number of bytes missing from that field.
+    ///
+    /// Lists that run to the end of a packet, such as the attributes of a response, carry
+    /// no length on the wire. Input that ends part way through an entry fails with
+    /// `nom::Err::Incomplete`, but input that ends exactly between two entries decodes
+    /// successfully with only the entries received so far. Responses that may be split
+    /// over several notifications should be collected with a `DataSourceReassembler`,
+    /// which knows how many attributes were requested.
+    ///
+    /// # Examples
+    /// ```
+    /// # use core::num::NonZeroUsize;
+    /// # use ancs::Decode;
+    /// # use ancs::attributes::NotificationAttributeRef;
+    /// let data: [u8; 7] = [1, 4, 0, 116, 101, 115, 116];
+    ///
+    /// assert_eq!(NotificationAttributeRef::decode_streaming(&data[..1]), Err(nom::Err::Incomplete(nom::Needed::Size(NonZeroUsize::new(2).unwrap()))));
+    /// assert_eq!(NotificationAttributeRef::decode_streaming(&data[..5]), Err(nom::Err::Incomplete(nom::Needed::Size(NonZeroUsize::new(2).unwrap()))));
+    /// assert_eq!(NotificationAttributeRef::decode_streaming(&data).unwrap().1.value, "test");
+    /// ```
+    ///
+    /// A response cut between two of its attributes can't be told apart from a complete one:
+    #[cfg_attr(feature = "alloc", doc = "```")]
+    #[cfg_attr(not(feature = "alloc"), doc = "```ignore")]
+    /// # use ancs::Decode;
+    /// # use ancs::characteristics::data_source::GetNotificationAttributesResponse;
+    /// let data: [u8; 15] = [0, 7, 0, 0, 0, 1, 2, 0, 104, 105, 3, 2, 0, 121, 111];
+    ///
+    /// assert!(matches!(GetNotificationAttributesResponse::decode_streaming(&data[..13]), Err(nom::Err::Incomplete(_))));
+    /// assert_eq!(GetNotificationAttributesResponse::decode_streaming(&data[..10]).unwrap().1.attribute_list.len(), 1);
+    /// assert_eq!(GetNotificationAttributesResponse::decode_streaming(&data).unwrap().1.attribute_list.len(), 2);
+    /// ```
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], Self, Error>;
+
+    /// Decodes a value from the start of `i`, which must hold all of it
+    ///
+    /// Fails with `Error::Truncated` where `decode_streaming` would fail with `nom::Err::Incomplete`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::{Decode, Error};
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// let data: [u8; 8] = [0, 0, 1, 1, 7, 0, 0, 0];
+    ///
+    /// assert_eq!(Notification::decode(&data).unwrap().1.notification_uid, 7);
+    /// assert_eq!(Notification::decode(&data[..6]), Err(nom::Err::Error(Error::Truncated)));
+    /// ```
+    fn decode(i: &'a [u8]) -> IResult<&'a [u8], Self, Error> {
+        match Self::decode_streaming(i) {
+            Err(nom::Err::Incomplete(_)) => Err(nom::Err::Error(Error::Truncated)),
+            result => result,
+        }
+    }
+}
+
+/// Applies `parser` until the input is exhausted.
+#[cfg(feature = "alloc")]
+pub(crate) fn many_to_end<'a, T>(
+    mut i: &'a [u8],
+    parser: impl Fn(&'a [u8]) -> IResult<&'a [u8], T, Error>,
+) -> IResult<&'a [u8], Vec<T>, Error> {
+    let mut items: Vec<T> = Vec::new();
+
+    while !i.is_empty() {
+        let (rest, item) = parser(i)?;
+        items.push(item);
+        i = rest;
+    }
+
+    Ok((i, items))
+}
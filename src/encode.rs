@@ -0,0 +1,194 @@
+//! ## Encoding
+//!
+//! Every packet and attribute type implements [`Encode`], which writes its wire
+//! representation into a caller-provided buffer without consuming or cloning the value.
+//! This lets firmware encode straight into a GATT TX buffer, the `From` and `TryFrom`
+//! conversions to `Vec<u8>` found throughout the crate are built on top of it.
+//!
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Error;
+
+/// A type that can be encoded as ANCS wire data.
+pub trait Encode {
+    /// Returns the exact number of bytes `encode_into` writes.
+    fn encoded_len(&self) -> usize;
+
+    /// Encodes `self` at the start of `buf` and returns the number of bytes written
+    ///
+    /// Fails with `Error::CapacityExceeded` if `buf` is shorter than `encoded_len`, the
+    /// contents of `buf` are unspecified after an error.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::{Encode, Error};
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::characteristics::control_point::PerformNotificationActionRequest;
+    /// let request = PerformNotificationActionRequest::new(1, ActionID::Negative);
+    /// let mut buf = [0_u8; 20];
+    ///
+    /// assert_eq!(request.encoded_len(), 6);
+    /// assert_eq!(request.encode_into(&mut buf), Ok(6));
+    /// assert_eq!(buf[..6], [2, 1, 0, 0, 0, 1]);
+    /// assert_eq!(request.encode_into(&mut buf[..4]), Err(Error::CapacityExceeded { capacity: 4 }));
+    /// ```
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error>;
+
+    /// Appends the encoded bytes to `vec` and returns the number of bytes written
+    ///
+    /// `vec` is left unchanged if encoding fails.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Encode;
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::characteristics::control_point::PerformNotificationActionRequest;
+    /// let mut vec: Vec<u8> = vec![42];
+    ///
+    /// PerformNotificationActionRequest::new(1, ActionID::Positive).encode_append(&mut vec).unwrap();
+    ///
+    /// assert_eq!(vec, [42, 2, 1, 0, 0, 0, 0]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn encode_append(&self, vec: &mut Vec<u8>) -> Result<usize, Error> {
+        let start = vec.len();
+        vec.resize(start + self.encoded_len(), 0);
+
+        match self.encode_into(&mut vec[start..]) {
+            Ok(written) => {
+                vec.truncate(start + written);
+                Ok(written)
+            }
+            Err(e) => {
+                vec.truncate(start);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Writes bytes sequentially into a slice, failing once it is full.
+pub(crate) struct Writer<'a> {
+    buf: &'a mut [u8],
+    written: usize,
+}
+
+impl<'a> Writer<'a> {
+    pub(crate) fn new(buf: &'a mut [u8]) -> Writer<'a> {
+        Writer { buf, written: 0 }
+    }
+
+    pub(crate) fn put(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let capacity = self.buf.len();
+
+        match self.buf.get_mut(self.written..self.written + bytes.len()) {
+            Some(target) => {
+                target.copy_from_slice(bytes);
+                self.written += bytes.len();
+                Ok(())
+            }
+            None => Err(Error::CapacityExceeded { capacity }),
+        }
+    }
+
+    pub(crate) fn put_u8(&mut self, byte: u8) -> Result<(), Error> {
+        self.put(&[byte])
+    }
+
+    /// Encodes a nested value.
+    #[cfg(any(feature = "alloc", feature = "heapless"))]
+    pub(crate) fn put_encoded<T: Encode + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let capacity = self.buf.len();
+        let written = value
+            .encode_into(&mut self.buf[self.written..])
+            .map_err(|e| match e {
+                Error::CapacityExceeded { .. } => Error::CapacityExceeded { capacity },
+                e => e,
+            })?;
+
+        self.written += written;
+        Ok(())
+    }
+
+    /// Writes a value through its `Display` implementation.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn put_display(&mut self, value: &impl fmt::Display) -> Result<(), Error> {
+        let capacity = self.buf.len();
+
+        fmt::write(self, format_args!("{}", value)).map_err(|_| Error::CapacityExceeded { capacity })
+    }
+
+    pub(crate) fn finish(self) -> usize {
+        self.written
+    }
+}
+
+impl fmt::Write for Writer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.put(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+/// Returns the number of bytes a value's `Display` implementation writes.
+#[cfg(feature = "alloc")]
+pub(crate) fn display_len(value: &impl fmt::Display) -> usize {
+    struct Counter(usize);
+
+    impl fmt::Write for Counter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    let _ = fmt::write(&mut counter, format_args!("{}", value));
+    counter.0
+}
+
+/// Returns an app identifier without its NULL terminator, as ANCS requires a non-empty one.
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+pub(crate) fn app_identifier(app_identifier: &str) -> Result<&[u8], Error> {
+    // Rust strings are not null terminated by default however it is possible
+    // that the user knows to insert a null terminator of some kind, it is
+    // stripped here so that exactly one is written.
+    let app_identifier = app_identifier.trim_end_matches('\0');
+
+    // An identifier that is nothing but its terminator can't name an app.
+    if app_identifier.is_empty() {
+        return Err(Error::EmptyAppIdentifier);
+    }
+
+    Ok(app_identifier.as_bytes())
+}
+
+/// Returns the encoded length of an app identifier including its NULL terminator.
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+pub(crate) fn app_identifier_len(app_identifier: &str) -> usize {
+    app_identifier.trim_end_matches('\0').len() + 1
+}
+
+/// Checks that an attribute's declared length agrees with its value.
+pub(crate) fn check_length(length: u16, actual: usize) -> Result<(), Error> {
+    // The length is sent ahead of the value so the two must agree or the
+    // receiver will read the wrong number of bytes.
+    match usize::from(length) == actual {
+        true => Ok(()),
+        false => Err(Error::LengthMismatch {
+            expected: length.into(),
+            actual,
+        }),
+    }
+}
+
+/// Encodes `value` into a new `Vec<u8>`.
+#[cfg(feature = "alloc")]
+pub(crate) fn to_vec<T: Encode + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut vec: Vec<u8> = Vec::new();
+
+    value.encode_append(&mut vec)?;
+
+    Ok(vec)
+}
@@ -0,0 +1,173 @@
+//! ## Errors
+//!
+//! Every parser and encoder in this crate reports failures through the single
+//! [`Error`] type found here. Parsers return it wrapped in a `nom::Err` so they
+//! can still be composed with other `nom` combinators, while encoders return it
+//! directly.
+//!
+use crate::attributes::command::CommandID;
+use crate::attributes::notification::NotificationAttributeID;
+use crate::characteristics::control_point::AncsErrorCode;
+
+use nom::error::{ErrorKind, FromExternalError, ParseError};
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// The `Error` type. See [the module level documentation](index.html) for more.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error {
+    /// A string attribute was not valid UTF-8, `offset` is the index of the
+    /// first invalid byte within that string.
+    InvalidUtf8 { offset: usize },
+    UnknownCommandID(u8),
+    UnknownNotificationAttributeID(u8),
+    UnknownAppAttributeID(u8),
+    UnknownCategoryID(u8),
+    UnknownEventID(u8),
+    UnknownActionID(u8),
+    /// The input ended before a complete value could be read.
+    Truncated,
+    /// An app identifier was empty, ANCS requires a non-empty NULL terminated string.
+    EmptyAppIdentifier,
+    /// An app identifier contained a NULL byte before its terminator.
+    InvalidAppIdentifier,
+    /// A date was not a valid `yyyyMMdd'T'HHmmSS` timestamp.
+    InvalidDate,
+    /// An attribute's declared length does not match the length of its value.
+    LengthMismatch { expected: usize, actual: usize },
+    /// A sized attribute was requested without a maximum length, or another attribute with one.
+    MaxLengthMismatch(NotificationAttributeID),
+    /// A packet carried a different command than the one expected.
+    CommandIDMismatch { expected: CommandID, actual: CommandID },
+    /// A response was for a different notification than the one requested.
+    NotificationUIDMismatch { expected: u32, actual: u32 },
+    /// A response was for a different app than the one requested.
+    AppIdentifierMismatch,
+    /// More bytes arrived than the requested attributes account for.
+    ResponseOverflow { excess: usize },
+    /// A response arrived while no command was waiting for one.
+    UnsolicitedResponse,
+    /// A command's response didn't arrive before its deadline.
+    Timeout,
+    /// A Control Point write failed in the transport rather than being answered by the iOS device.
+    WriteFailed,
+    /// The iOS device rejected a Control Point write.
+    ControlPoint(AncsErrorCode),
+    /// A Control Point write failed with an ATT error that isn't defined by ANCS.
+    UnknownErrorCode(u8),
+    /// A fixed-capacity buffer was too small to hold a value.
+    CapacityExceeded { capacity: usize },
+    /// Any other failure reported by an underlying `nom` combinator.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::error_kind"))]
+    Malformed(ErrorKind),
+}
+
+impl fmt::Display for Error {
+    /// Formats an `Error` as a human readable message
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// let error = Error::UnknownCategoryID(42);
+    ///
+    /// assert_eq!(error.to_string(), "unknown category ID 42");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidUtf8 { offset } => write!(f, "invalid UTF-8 at offset {}", offset),
+            Error::UnknownCommandID(id) => write!(f, "unknown command ID {}", id),
+            Error::UnknownNotificationAttributeID(id) => write!(f, "unknown notification attribute ID {}", id),
+            Error::UnknownAppAttributeID(id) => write!(f, "unknown app attribute ID {}", id),
+            Error::UnknownCategoryID(id) => write!(f, "unknown category ID {}", id),
+            Error::UnknownEventID(id) => write!(f, "unknown event ID {}", id),
+            Error::UnknownActionID(id) => write!(f, "unknown action ID {}", id),
+            Error::Truncated => write!(f, "input ended unexpectedly"),
+            Error::EmptyAppIdentifier => write!(f, "app identifier is empty"),
+            Error::InvalidAppIdentifier => write!(f, "app identifier contains a NULL byte"),
+            Error::InvalidDate => write!(f, "invalid date"),
+            Error::LengthMismatch { expected, actual } => {
+                write!(f, "length mismatch, expected {} bytes but found {}", expected, actual)
+            }
+            Error::MaxLengthMismatch(id) => match NotificationAttributeID::is_sized(*id) {
+                true => write!(f, "attribute {:?} requires a maximum length", id),
+                false => write!(f, "attribute {:?} does not take a maximum length", id),
+            },
+            Error::CommandIDMismatch { expected, actual } => {
+                write!(f, "expected command {:?} but found {:?}", expected, actual)
+            }
+            Error::NotificationUIDMismatch { expected, actual } => {
+                write!(f, "expected notification UID {} but found {}", expected, actual)
+            }
+            Error::AppIdentifierMismatch => write!(f, "app identifier does not match the request"),
+            Error::ResponseOverflow { excess } => write!(f, "response overflowed by {} bytes", excess),
+            Error::UnsolicitedResponse => write!(f, "response received with no command in flight"),
+            Error::Timeout => write!(f, "command timed out"),
+            Error::WriteFailed => write!(f, "control point write failed in the transport"),
+            Error::ControlPoint(code) => write!(f, "control point write failed ({:?})", code),
+            Error::UnknownErrorCode(code) => write!(f, "control point write failed with ATT error {:#04X}", code),
+            Error::CapacityExceeded { capacity } => write!(f, "capacity of {} exceeded", capacity),
+            Error::Malformed(kind) => write!(f, "malformed input ({})", kind.description()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<AncsErrorCode> for Error {
+    fn from(original: AncsErrorCode) -> Error {
+        Error::ControlPoint(original)
+    }
+}
+
+impl<I> ParseError<I> for Error {
+    fn from_error_kind(_input: I, kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::Eof => Error::Truncated,
+            kind => Error::Malformed(kind),
+        }
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<I, E> FromExternalError<I, E> for Error {
+    fn from_external_error(input: I, kind: ErrorKind, _e: E) -> Self {
+        Error::from_error_kind(input, kind)
+    }
+}
+
+impl From<nom::Err<Error>> for Error {
+    /// Flattens a `nom::Err<Error>` returned by a parser into an `Error`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::category::CategoryID;
+    /// let error: Error = CategoryID::parse_strict(&[42]).unwrap_err().into();
+    ///
+    /// assert_eq!(error, Error::UnknownCategoryID(42));
+    /// ```
+    fn from(original: nom::Err<Error>) -> Error {
+        match original {
+            nom::Err::Incomplete(_) => Error::Truncated,
+            nom::Err::Error(error) => error,
+            nom::Err::Failure(error) => error,
+        }
+    }
+}
+
+/// Converts a byte slice into a `&str`, mapping invalid UTF-8 to an `Error`.
+pub(crate) fn utf8_str(bytes: &[u8]) -> Result<&str, Error> {
+    core::str::from_utf8(bytes).map_err(|e| Error::InvalidUtf8 { offset: e.valid_up_to() })
+}
+
+/// Converts a byte slice into a `String`, mapping invalid UTF-8 to an `Error`.
+#[cfg(feature = "alloc")]
+pub(crate) fn utf8(bytes: &[u8]) -> Result<String, Error> {
+    utf8_str(bytes).map(ToString::to_string)
+}
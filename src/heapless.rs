@@ -0,0 +1,531 @@
+//! ## Heapless
+//!
+//! Allocation-free versions of the attribute, request and response types for targets
+//! without a heap. Every variable length field is stored in a fixed-capacity
+//! `heapless::String` or `heapless::Vec` whose capacity is set through const generics,
+//! values that don't fit are rejected with `Error::CapacityExceeded` instead of allocating.
+//!
+//! `Notification` and `PerformNotificationActionRequest` never allocate, so they are
+//! shared with the rest of the crate and only gain conversions to `heapless::Vec` here.
+//!
+use ::heapless::{String, Vec};
+use nom::{
+    bytes::streaming::{take, take_till},
+    number::streaming::{le_u16, le_u32, le_u8},
+    sequence::terminated,
+    IResult,
+};
+
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::command::CommandID;
+use crate::attributes::date::DateTime;
+use crate::attributes::notification::{NotificationAttributeID, RequestedAttribute};
+use crate::characteristics::control_point::PerformNotificationActionRequest;
+use crate::decode::Decode;
+use crate::encode::{app_identifier, app_identifier_len, check_length, Encode, Writer};
+use crate::error::{utf8_str, Error};
+
+/// The `NotificationAttribute` type holding at most `N` bytes of value.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotificationAttribute<const N: usize> {
+    pub id: NotificationAttributeID,
+    pub length: u16,
+    pub value: String<N>,
+}
+
+impl<const N: usize, const B: usize> TryFrom<NotificationAttribute<N>> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert a `NotificationAttribute` to a `heapless::Vec<u8, B>`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::heapless::NotificationAttribute;
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// let attribute: NotificationAttribute<8> = NotificationAttribute {
+    ///     id: NotificationAttributeID::Title,
+    ///     length: 4,
+    ///     value: heapless::String::try_from("test").unwrap(),
+    /// };
+    ///
+    /// let data: heapless::Vec<u8, 16> = attribute.try_into().unwrap();
+    ///
+    /// assert_eq!(data, [1, 4, 0, 116, 101, 115, 116]);
+    /// ```
+    fn try_from(original: NotificationAttribute<N>) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+impl<const N: usize> Encode for NotificationAttribute<N> {
+    fn encoded_len(&self) -> usize {
+        3 + self.value.len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        encode_attribute(buf, self.id.into(), self.length, &self.value)
+    }
+}
+
+impl<const N: usize> NotificationAttribute<N> {
+    /// Attempts to parse a `NotificationAttribute` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::heapless::NotificationAttribute;
+    /// # use ancs::attributes::notification::NotificationAttributeID;
+    /// let bytes: [u8; 7] = [0, 4, 0, 116, 101, 115, 116];
+    /// let (_, attribute) = NotificationAttribute::<8>::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(attribute.id, NotificationAttributeID::AppIdentifier);
+    /// assert_eq!(attribute.value, "test");
+    ///
+    /// // Values longer than the capacity are rejected
+    /// assert_eq!(NotificationAttribute::<2>::parse(&bytes), Err(nom::Err::Failure(Error::CapacityExceeded { capacity: 2 })));
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], NotificationAttribute<N>, Error> {
+        NotificationAttribute::decode(i)
+    }
+
+    /// Returns the value of a `NotificationAttributeID::Date` attribute.
+    pub fn date(&self) -> Option<DateTime> {
+        match self.id {
+            NotificationAttributeID::Date => self.value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `NotificationAttributeID::MessageSize` attribute.
+    pub fn size(&self) -> Option<u32> {
+        match self.id {
+            NotificationAttributeID::MessageSize => self.value.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, const N: usize> Decode<'a> for NotificationAttribute<N> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], NotificationAttribute<N>, Error> {
+        let (i, id) = NotificationAttributeID::decode_streaming(i)?;
+        let (i, length) = le_u16(i)?;
+        let (i, value) = take(length)(i)?;
+
+        Ok((
+            i,
+            NotificationAttribute {
+                id,
+                length,
+                value: string(value)?,
+            },
+        ))
+    }
+}
+
+/// The `AppAttribute` type holding at most `N` bytes of value.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppAttribute<const N: usize> {
+    pub id: AppAttributeID,
+    pub length: u16,
+    pub value: String<N>,
+}
+
+impl<const N: usize, const B: usize> TryFrom<AppAttribute<N>> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert an `AppAttribute` to a `heapless::Vec<u8, B>`
+    fn try_from(original: AppAttribute<N>) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+impl<const N: usize> Encode for AppAttribute<N> {
+    fn encoded_len(&self) -> usize {
+        3 + self.value.len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        encode_attribute(buf, self.id.into(), self.length, &self.value)
+    }
+}
+
+impl<const N: usize> AppAttribute<N> {
+    /// Attempts to parse an `AppAttribute` from a `&[u8]`
+    pub fn parse(i: &[u8]) -> IResult<&[u8], AppAttribute<N>, Error> {
+        AppAttribute::decode(i)
+    }
+}
+
+impl<'a, const N: usize> Decode<'a> for AppAttribute<N> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], AppAttribute<N>, Error> {
+        let (i, id) = AppAttributeID::decode_streaming(i)?;
+        let (i, length) = le_u16(i)?;
+        let (i, value) = take(length)(i)?;
+
+        Ok((
+            i,
+            AppAttribute {
+                id,
+                length,
+                value: string(value)?,
+            },
+        ))
+    }
+}
+
+/// The `GetNotificationAttributesRequest` type holding at most `A` attributes.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetNotificationAttributesRequest<const A: usize> {
+    pub notification_uid: u32,
+    pub attribute_ids: Vec<RequestedAttribute, A>,
+}
+
+impl<const A: usize, const B: usize> TryFrom<GetNotificationAttributesRequest<A>> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetNotificationAttributesRequest` to a `heapless::Vec<u8, B>`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::notification::RequestedAttribute;
+    /// # use ancs::heapless::GetNotificationAttributesRequest;
+    /// let request: GetNotificationAttributesRequest<4> = GetNotificationAttributesRequest {
+    ///     notification_uid: 4294967295_u32,
+    ///     attribute_ids: heapless::Vec::from_slice(&[RequestedAttribute::AppIdentifier, RequestedAttribute::Title(u16::MAX)]).unwrap(),
+    /// };
+    ///
+    /// let data: heapless::Vec<u8, 16> = request.try_into().unwrap();
+    ///
+    /// assert_eq!(data, [0, 255, 255, 255, 255, 0, 1, 255, 255]);
+    /// ```
+    fn try_from(original: GetNotificationAttributesRequest<A>) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+impl<const A: usize> Encode for GetNotificationAttributesRequest<A> {
+    fn encoded_len(&self) -> usize {
+        5 + self.attribute_ids.iter().map(Encode::encoded_len).sum::<usize>()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetNotificationAttributesRequest::<A>::COMMAND_ID.into())?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
+
+        for attribute in &self.attribute_ids {
+            writer.put_encoded(attribute)?;
+        }
+
+        Ok(writer.finish())
+    }
+}
+
+impl<const A: usize> GetNotificationAttributesRequest<A> {
+    pub const COMMAND_ID: CommandID = CommandID::GetNotificationAttributes;
+
+    /// Attempts to parse a `GetNotificationAttributesRequest` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::heapless::GetNotificationAttributesRequest;
+    /// let data: [u8; 9] = [0, 255, 255, 255, 255, 0, 1, 255, 255];
+    /// let (_, request) = GetNotificationAttributesRequest::<2>::parse(&data).unwrap();
+    ///
+    /// assert_eq!(request.notification_uid, 4294967295_u32);
+    /// assert_eq!(request.attribute_ids.len(), 2);
+    /// assert_eq!(GetNotificationAttributesRequest::<1>::parse(&data), Err(nom::Err::Failure(Error::CapacityExceeded { capacity: 1 })));
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetNotificationAttributesRequest<A>, Error> {
+        GetNotificationAttributesRequest::decode(i)
+    }
+}
+
+impl<'a, const A: usize> Decode<'a> for GetNotificationAttributesRequest<A> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetNotificationAttributesRequest<A>, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetNotificationAttributesRequest::<A>::COMMAND_ID)?;
+        let (i, notification_uid) = le_u32(i)?;
+        let (i, attribute_ids) = many_to_end(i, RequestedAttribute::decode_streaming)?;
+
+        Ok((
+            i,
+            GetNotificationAttributesRequest {
+                notification_uid,
+                attribute_ids,
+            },
+        ))
+    }
+}
+
+/// The `GetAppAttributesRequest` type holding an app identifier of at most `I` bytes and `A` attributes.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetAppAttributesRequest<const I: usize, const A: usize> {
+    pub app_identifier: String<I>,
+    pub attribute_ids: Vec<AppAttributeID, A>,
+}
+
+impl<const I: usize, const A: usize, const B: usize> TryFrom<GetAppAttributesRequest<I, A>> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetAppAttributesRequest` to a `heapless::Vec<u8, B>`
+    fn try_from(original: GetAppAttributesRequest<I, A>) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+impl<const I: usize, const A: usize> Encode for GetAppAttributesRequest<I, A> {
+    fn encoded_len(&self) -> usize {
+        1 + app_identifier_len(&self.app_identifier) + self.attribute_ids.len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let app_identifier = app_identifier(&self.app_identifier)?;
+
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetAppAttributesRequest::<I, A>::COMMAND_ID.into())?;
+        writer.put(app_identifier)?;
+        writer.put_u8(0)?;
+
+        for id in &self.attribute_ids {
+            writer.put_u8((*id).into())?;
+        }
+
+        Ok(writer.finish())
+    }
+}
+
+impl<const I: usize, const A: usize> GetAppAttributesRequest<I, A> {
+    pub const COMMAND_ID: CommandID = CommandID::GetAppAttributes;
+
+    /// Attempts to parse a `GetAppAttributesRequest` from a `&[u8]`
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetAppAttributesRequest<I, A>, Error> {
+        GetAppAttributesRequest::decode(i)
+    }
+}
+
+impl<'a, const I: usize, const A: usize> Decode<'a> for GetAppAttributesRequest<I, A> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetAppAttributesRequest<I, A>, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetAppAttributesRequest::<I, A>::COMMAND_ID)?;
+        let (i, app_identifier) = terminated(take_till(|b| b == 0), le_u8)(i)?;
+        let (i, attribute_ids) = many_to_end(i, AppAttributeID::decode_streaming)?;
+
+        Ok((
+            i,
+            GetAppAttributesRequest {
+                app_identifier: string(app_identifier)?,
+                attribute_ids,
+            },
+        ))
+    }
+}
+
+impl<const B: usize> TryFrom<PerformNotificationActionRequest> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert a `PerformNotificationActionRequest` to a `heapless::Vec<u8, B>`
+    fn try_from(original: PerformNotificationActionRequest) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+/// The `GetNotificationAttributesResponse` type holding `A` attributes of at most `N` bytes each.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetNotificationAttributesResponse<const N: usize, const A: usize> {
+    pub notification_uid: u32,
+    pub attribute_list: Vec<NotificationAttribute<N>, A>,
+}
+
+impl<const N: usize, const A: usize, const B: usize> TryFrom<GetNotificationAttributesResponse<N, A>> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetNotificationAttributesResponse` to a `heapless::Vec<u8, B>`
+    fn try_from(original: GetNotificationAttributesResponse<N, A>) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+impl<const N: usize, const A: usize> Encode for GetNotificationAttributesResponse<N, A> {
+    fn encoded_len(&self) -> usize {
+        5 + self.attribute_list.iter().map(Encode::encoded_len).sum::<usize>()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetNotificationAttributesResponse::<N, A>::COMMAND_ID.into())?;
+        writer.put(&self.notification_uid.to_le_bytes())?;
+
+        for attribute in &self.attribute_list {
+            writer.put_encoded(attribute)?;
+        }
+
+        Ok(writer.finish())
+    }
+}
+
+impl<const N: usize, const A: usize> GetNotificationAttributesResponse<N, A> {
+    pub const COMMAND_ID: CommandID = CommandID::GetNotificationAttributes;
+
+    /// Attempts to parse a `GetNotificationAttributesResponse` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::heapless::GetNotificationAttributesResponse;
+    /// let bytes: [u8; 21] = [0, 255, 255, 255, 255, 0, 13, 0, 99, 111, 109, 46, 114, 117, 115, 116, 46, 116, 101, 115, 116];
+    /// let (_, response) = GetNotificationAttributesResponse::<32, 4>::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(response.notification_uid, 4294967295_u32);
+    /// assert_eq!(response.attribute_list[0].value, "com.rust.test");
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetNotificationAttributesResponse<N, A>, Error> {
+        GetNotificationAttributesResponse::decode(i)
+    }
+}
+
+impl<'a, const N: usize, const A: usize> Decode<'a> for GetNotificationAttributesResponse<N, A> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetNotificationAttributesResponse<N, A>, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetNotificationAttributesResponse::<N, A>::COMMAND_ID)?;
+        let (i, notification_uid) = le_u32(i)?;
+        let (i, attribute_list) = many_to_end(i, NotificationAttribute::decode_streaming)?;
+
+        Ok((
+            i,
+            GetNotificationAttributesResponse {
+                notification_uid,
+                attribute_list,
+            },
+        ))
+    }
+}
+
+/// The `GetAppAttributesResponse` type holding an app identifier of at most `I` bytes
+/// and `A` attributes of at most `N` bytes each.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetAppAttributesResponse<const I: usize, const N: usize, const A: usize> {
+    pub app_identifier: String<I>,
+    pub attribute_list: Vec<AppAttribute<N>, A>,
+}
+
+impl<const I: usize, const N: usize, const A: usize, const B: usize> TryFrom<GetAppAttributesResponse<I, N, A>> for Vec<u8, B> {
+    type Error = Error;
+
+    /// Attempts to convert a `GetAppAttributesResponse` to a `heapless::Vec<u8, B>`
+    fn try_from(original: GetAppAttributesResponse<I, N, A>) -> Result<Vec<u8, B>, Error> {
+        to_vec(&original)
+    }
+}
+
+impl<const I: usize, const N: usize, const A: usize> Encode for GetAppAttributesResponse<I, N, A> {
+    fn encoded_len(&self) -> usize {
+        1 + app_identifier_len(&self.app_identifier) + self.attribute_list.iter().map(Encode::encoded_len).sum::<usize>()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let app_identifier = app_identifier(&self.app_identifier)?;
+
+        let mut writer = Writer::new(buf);
+        writer.put_u8(GetAppAttributesResponse::<I, N, A>::COMMAND_ID.into())?;
+        writer.put(app_identifier)?;
+        writer.put_u8(0)?;
+
+        for attribute in &self.attribute_list {
+            writer.put_encoded(attribute)?;
+        }
+
+        Ok(writer.finish())
+    }
+}
+
+impl<const I: usize, const N: usize, const A: usize> GetAppAttributesResponse<I, N, A> {
+    pub const COMMAND_ID: CommandID = CommandID::GetAppAttributes;
+
+    /// Attempts to parse a `GetAppAttributesResponse` from a `&[u8]`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::heapless::GetAppAttributesResponse;
+    /// let data: [u8; 23] = [1, 99, 111, 109, 46, 97, 112, 112, 108, 101, 46, 116, 101, 115, 116, 0, 0, 4, 0, 84, 101, 115, 116];
+    /// let (_, response) = GetAppAttributesResponse::<16, 8, 1>::parse(&data).unwrap();
+    ///
+    /// assert_eq!(response.app_identifier, "com.apple.test");
+    /// assert_eq!(response.attribute_list[0].value, "Test");
+    /// ```
+    pub fn parse(i: &[u8]) -> IResult<&[u8], GetAppAttributesResponse<I, N, A>, Error> {
+        GetAppAttributesResponse::decode(i)
+    }
+}
+
+impl<'a, const I: usize, const N: usize, const A: usize> Decode<'a> for GetAppAttributesResponse<I, N, A> {
+    fn decode_streaming(i: &'a [u8]) -> IResult<&'a [u8], GetAppAttributesResponse<I, N, A>, Error> {
+        let (i, _) = CommandID::parse_expected(i, GetAppAttributesResponse::<I, N, A>::COMMAND_ID)?;
+        let (i, app_identifier) = terminated(take_till(|b| b == 0), le_u8)(i)?;
+        let (i, attribute_list) = many_to_end(i, AppAttribute::decode_streaming)?;
+
+        Ok((
+            i,
+            GetAppAttributesResponse {
+                app_identifier: string(app_identifier)?,
+                attribute_list,
+            },
+        ))
+    }
+}
+
+/// Applies `parser` until the input is exhausted, collecting into a `heapless::Vec`.
+fn many_to_end<'a, T, const A: usize>(
+    mut i: &'a [u8],
+    parser: impl Fn(&'a [u8]) -> IResult<&'a [u8], T, Error>,
+) -> IResult<&'a [u8], Vec<T, A>, Error> {
+    let mut items: Vec<T, A> = Vec::new();
+
+    while !i.is_empty() {
+        let (rest, item) = parser(i)?;
+
+        if items.push(item).is_err() {
+            return Err(nom::Err::Failure(Error::CapacityExceeded { capacity: A }));
+        }
+
+        i = rest;
+    }
+
+    Ok((i, items))
+}
+
+/// Converts UTF-8 bytes into a `heapless::String`.
+fn string<const N: usize>(bytes: &[u8]) -> Result<String<N>, nom::Err<Error>> {
+    let mut value: String<N> = String::new();
+
+    value
+        .push_str(utf8_str(bytes).map_err(nom::Err::Failure)?)
+        .map_err(|_| nom::Err::Failure(Error::CapacityExceeded { capacity: N }))?;
+
+    Ok(value)
+}
+
+/// Encodes `value` into a new `heapless::Vec<u8, B>`.
+fn to_vec<T: Encode, const B: usize>(value: &T) -> Result<Vec<u8, B>, Error> {
+    let mut vec: Vec<u8, B> = Vec::new();
+
+    vec.resize(value.encoded_len(), 0).map_err(|_| Error::CapacityExceeded { capacity: B })?;
+    let written = value.encode_into(&mut vec)?;
+    vec.truncate(written);
+
+    Ok(vec)
+}
+
+fn encode_attribute(buf: &mut [u8], id: u8, length: u16, value: &str) -> Result<usize, Error> {
+    check_length(length, value.len())?;
+
+    let mut writer = Writer::new(buf);
+    writer.put_u8(id)?;
+    writer.put(&length.to_le_bytes())?;
+    writer.put(value.as_bytes())?;
+
+    Ok(writer.finish())
+}
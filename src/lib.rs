@@ -11,15 +11,46 @@
 //! ## Apple Notification Control Service Protocol
 //! 
 //! > The purpose of the Apple Notification Control Center Service is to give Bluetooth
-//! accessories (that connect to iOS devices through a Bluetooth low-energy link) a 
-//! simple convenient way to access many kinds of notifications that are generated on 
-//! iOS devices.
+//! > accessories (that connect to iOS devices through a Bluetooth low-energy link) a 
+//! > simple convenient way to access many kinds of notifications that are generated on 
+//! > iOS devices.
 //! 
 //! The ANCS protocol utilizes Bluetooth low-energy and a GATT Service, Characteristics and 
 //! Attributes to handle all data transport over Bluetooth low-energy. This library allows
 //! for easy serialization and deserilization of the wire data for this protocol.
 //! 
+//! ## `no_std` Support
+//! 
+//! The crate is `no_std` compatible when its default `std` feature is disabled, enabling
+//! the `alloc` feature keeps every type that needs the `alloc` crate available. Targets
+//! without a heap can instead enable the `heapless` feature and use the fixed-capacity
+//! types found in the `heapless` module.
+//! 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod attributes;
 pub mod characteristics;
+#[cfg(feature = "alloc")]
+pub mod client;
+pub mod decode;
+pub mod encode;
+pub mod error;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "alloc")]
+pub mod pending;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "alloc")]
+pub mod store;
+#[cfg(feature = "std")]
+pub mod transport;
+
+pub use decode::Decode;
+pub use encode::Encode;
+pub use error::Error;
 
 pub const APPLE_NOTIFICATION_CENTER_SERVICE_UUID: &str = "7905F431-B5CE-4E99-A40F-4B1E122D00D0";
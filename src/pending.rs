@@ -0,0 +1,186 @@
+//! ## Pending Commands
+//!
+//! iOS may never answer a request written to the Control Point, such as when it drops a
+//! Data Source notification. The `PendingCommands` tracker remembers every request along
+//! with a deadline and hands back the ones whose deadline passed, so they can be aborted
+//! and a lost response can't hold up the commands queued behind it. Responses are matched
+//! to their request by the `AncsClient`, not by the tracker.
+//!
+//! Time is read through the `Clock` trait, which lets firmware plug in its own timer and
+//! tests drive the tracker with a fake clock.
+//!
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::attributes::command::CommandID;
+use crate::characteristics::control_point::ControlPointRequest;
+
+/// A source of the current time.
+pub trait Clock {
+    /// A point in time, later instants compare greater than earlier ones.
+    type Instant: Copy + Ord;
+
+    fn now(&self) -> Self::Instant;
+}
+
+/// A `Clock` reading `std::time::Instant::now`.
+#[cfg(feature = "std")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    type Instant = std::time::Instant;
+
+    fn now(&self) -> std::time::Instant {
+        std::time::Instant::now()
+    }
+}
+
+/// Identifies a request by its `CommandID` and the notification or app it is for.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CommandKey {
+    GetNotificationAttributes(u32),
+    /// Holds the app identifier without its NULL terminator.
+    GetAppAttributes(String),
+    PerformNotificationAction(u32),
+}
+
+impl CommandKey {
+    /// Returns the `CommandID` of the request.
+    pub fn command_id(&self) -> CommandID {
+        match self {
+            CommandKey::GetNotificationAttributes(_) => CommandID::GetNotificationAttributes,
+            CommandKey::GetAppAttributes(_) => CommandID::GetAppAttributes,
+            CommandKey::PerformNotificationAction(_) => CommandID::PerformNotificationAction,
+        }
+    }
+}
+
+impl From<&ControlPointRequest> for CommandKey {
+    fn from(original: &ControlPointRequest) -> CommandKey {
+        match original {
+            ControlPointRequest::GetNotificationAttributes(request) => {
+                CommandKey::GetNotificationAttributes(request.notification_uid)
+            }
+            ControlPointRequest::GetAppAttributes(request) => {
+                CommandKey::GetAppAttributes(request.app_identifier.trim_end_matches('\0').into())
+            }
+            ControlPointRequest::PerformNotificationAction(request) => {
+                CommandKey::PerformNotificationAction(request.notification_uid)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct PendingCommand<I> {
+    key: CommandKey,
+    request: ControlPointRequest,
+    deadline: I,
+}
+
+/// The `PendingCommands` type. See [the module level documentation](index.html) for more.
+///
+/// # Examples
+/// ```
+/// # use std::cell::Cell;
+/// # use ancs::attributes::notification::RequestedAttribute;
+/// # use ancs::characteristics::control_point::GetNotificationAttributesRequest;
+/// # use ancs::pending::{Clock, PendingCommands};
+/// struct FakeClock(Cell<u32>);
+///
+/// impl Clock for &FakeClock {
+///     type Instant = u32;
+///
+///     fn now(&self) -> u32 {
+///         self.0.get()
+///     }
+/// }
+///
+/// let clock = FakeClock(Cell::new(0));
+/// let mut pending = PendingCommands::new(&clock);
+///
+/// let request = GetNotificationAttributesRequest::new(7, vec![RequestedAttribute::Title(32)]);
+/// pending.insert(request.into(), 100);
+///
+/// clock.0.set(99);
+/// assert!(pending.expire().is_empty());
+///
+/// clock.0.set(100);
+/// assert_eq!(pending.expire().len(), 1);
+/// assert!(pending.is_empty());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingCommands<C: Clock> {
+    clock: C,
+    commands: Vec<PendingCommand<C::Instant>>,
+}
+
+impl<C: Clock> PendingCommands<C> {
+    pub fn new(clock: C) -> PendingCommands<C> {
+        PendingCommands {
+            clock,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Tracks a request written to the Control Point until it is removed or `deadline` passes.
+    ///
+    /// Identical requests are removed in the order they were inserted.
+    pub fn insert(&mut self, request: ControlPointRequest, deadline: C::Instant) {
+        self.commands.push(PendingCommand {
+            key: CommandKey::from(&request),
+            request,
+            deadline,
+        });
+    }
+
+    /// Removes and returns the oldest pending request with the given key, such as once
+    /// its response arrived.
+    pub fn remove(&mut self, key: &CommandKey) -> Option<ControlPointRequest> {
+        let index = self.commands.iter().position(|command| command.key == *key)?;
+
+        Some(self.commands.remove(index).request)
+    }
+
+    /// Removes and returns every request whose deadline is at or before the clock's current time.
+    pub fn expire(&mut self) -> Vec<ControlPointRequest> {
+        let now = self.clock.now();
+        let mut expired: Vec<ControlPointRequest> = Vec::new();
+
+        self.commands.retain(|command| match command.deadline <= now {
+            true => {
+                expired.push(command.request.clone());
+                false
+            }
+            false => true,
+        });
+
+        expired
+    }
+
+    /// Returns the earliest deadline of any pending request, which is when `expire` should next be called.
+    pub fn next_deadline(&self) -> Option<C::Instant> {
+        self.commands.iter().map(|command| command.deadline).min()
+    }
+
+    /// Determines if a request with the given key is pending.
+    pub fn contains(&self, key: &CommandKey) -> bool {
+        self.commands.iter().any(|command| command.key == *key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Drops every pending request, such as after the iOS device disconnects.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
@@ -0,0 +1,261 @@
+//! ## Serde
+//!
+//! The attribute, request and response types, `Error` and the client events along with the
+//! types in `store` derive `Serialize` and `Deserialize` when the `serde` feature is enabled.
+//! Types holding session state, such as `AncsClient`, `DataSourceReassembler` and
+//! `PendingCommands`, and the request builders don't.
+//!
+//! ID enums are serialized by name, e.g. `"IncomingCall"`, so the output stays readable and
+//! doesn't change if the numbering is reordered. Fields that should carry the raw ANCS byte
+//! instead can opt in with `#[serde(with = "ancs::serde::numeric")]`.
+//!
+//! `DateTime` is serialized as its ANCS `yyyyMMdd'T'HHmmSS` string and `EventFlags` as its
+//! raw bits, which keeps any reserved bits intact.
+//!
+//! ```
+//! # use ancs::attributes::category::CategoryID;
+//! # use ancs::attributes::event::{EventFlag, EventID};
+//! # use ancs::characteristics::notification_source::Notification;
+//! let notification = Notification {
+//!     event_id: EventID::NotificationAdded,
+//!     event_flags: EventFlag::Important.into(),
+//!     category_id: CategoryID::IncomingCall,
+//!     category_count: 1,
+//!     notification_uid: 42,
+//! };
+//! let json = serde_json::to_string(&notification).unwrap();
+//!
+//! assert_eq!(
+//!     json,
+//!     r#"{"event_id":"NotificationAdded","event_flags":2,"category_id":"IncomingCall","category_count":1,"notification_uid":42}"#,
+//! );
+//! assert_eq!(serde_json::from_str::<Notification>(&json).unwrap(), notification);
+//! ```
+//!
+//! An `Unknown` variant holding an ID that ANCS defines is serialized and deserialized as
+//! the variant for that ID, the same way it compares equal to it.
+//!
+//! ```
+//! # use ancs::attributes::category::CategoryID;
+//! assert_eq!(serde_json::to_string(&CategoryID::Unknown(3)).unwrap(), r#""Voicemail""#);
+//! assert_eq!(serde_json::to_string(&CategoryID::Unknown(200)).unwrap(), r#"{"Unknown":200}"#);
+//! assert!(matches!(serde_json::from_str(r#"{"Unknown":3}"#).unwrap(), CategoryID::Voicemail));
+//! ```
+//!
+use core::fmt;
+
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::ser::{SerializeStruct, Serializer};
+use ::serde::{Deserialize, Serialize};
+
+use crate::attributes::action::ActionID;
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::category::CategoryID;
+use crate::attributes::command::CommandID;
+use crate::attributes::date::DateTime;
+use crate::attributes::event::EventID;
+use crate::attributes::notification::{NotificationAttributeID, RequestedAttribute};
+use crate::characteristics::data_source::GetNotificationAttributesResponseRef;
+
+/// Serializes an ID enum as its raw ANCS byte rather than its name.
+///
+/// # Examples
+/// ```
+/// # use ancs::attributes::category::CategoryID;
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Log {
+///     #[serde(with = "ancs::serde::numeric")]
+///     category_id: CategoryID,
+/// }
+///
+/// let log = Log { category_id: CategoryID::IncomingCall };
+/// let json = serde_json::to_string(&log).unwrap();
+///
+/// assert_eq!(json, r#"{"category_id":1}"#);
+/// assert_eq!(serde_json::from_str::<Log>(&json).unwrap().category_id, CategoryID::IncomingCall);
+/// ```
+pub mod numeric {
+    use core::fmt::Display;
+
+    use ::serde::de::Error as _;
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<u8>,
+        S: Serializer,
+    {
+        serializer.serialize_u8((*value).into())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<u8>,
+        T::Error: Display,
+        D: Deserializer<'de>,
+    {
+        let value = u8::deserialize(deserializer)?;
+
+        T::try_from(value).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes the `nom::error::ErrorKind` of `Error::Malformed` as its description
+///
+/// # Examples
+/// ```
+/// # use ancs::Error;
+/// let error = Error::Malformed(nom::error::ErrorKind::Verify);
+/// let json = serde_json::to_string(&error).unwrap();
+///
+/// assert_eq!(json, r#"{"Malformed":"predicate verification"}"#);
+/// assert_eq!(serde_json::from_str::<Error>(&json).unwrap(), error);
+/// ```
+pub(crate) mod error_kind {
+    use core::fmt;
+
+    use ::serde::de::{self, Deserializer, Visitor};
+    use ::serde::Serializer;
+    use nom::error::ErrorKind;
+
+    const ERROR_KINDS: [ErrorKind; 53] = [
+        ErrorKind::Tag, ErrorKind::MapRes, ErrorKind::MapOpt, ErrorKind::Alt, ErrorKind::IsNot, ErrorKind::IsA,
+        ErrorKind::SeparatedList, ErrorKind::SeparatedNonEmptyList, ErrorKind::Many0, ErrorKind::Many1,
+        ErrorKind::ManyTill, ErrorKind::Count, ErrorKind::TakeUntil, ErrorKind::LengthValue,
+        ErrorKind::TagClosure, ErrorKind::Alpha, ErrorKind::Digit, ErrorKind::HexDigit, ErrorKind::OctDigit,
+        ErrorKind::AlphaNumeric, ErrorKind::Space, ErrorKind::MultiSpace, ErrorKind::LengthValueFn,
+        ErrorKind::Eof, ErrorKind::Switch, ErrorKind::TagBits, ErrorKind::OneOf, ErrorKind::NoneOf,
+        ErrorKind::Char, ErrorKind::CrLf, ErrorKind::RegexpMatch, ErrorKind::RegexpMatches,
+        ErrorKind::RegexpFind, ErrorKind::RegexpCapture, ErrorKind::RegexpCaptures, ErrorKind::TakeWhile1,
+        ErrorKind::Complete, ErrorKind::Fix, ErrorKind::Escaped, ErrorKind::EscapedTransform,
+        ErrorKind::NonEmpty, ErrorKind::ManyMN, ErrorKind::Not, ErrorKind::Permutation, ErrorKind::Verify,
+        ErrorKind::TakeTill1, ErrorKind::TakeWhileMN, ErrorKind::TooLarge, ErrorKind::Many0Count,
+        ErrorKind::Many1Count, ErrorKind::Float, ErrorKind::Satisfy, ErrorKind::Fail,
+    ];
+
+    pub fn serialize<S: Serializer>(kind: &ErrorKind, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(kind.description())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ErrorKind, D::Error> {
+        struct ErrorKindVisitor;
+
+        impl Visitor<'_> for ErrorKindVisitor {
+            type Value = ErrorKind;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a nom error kind description")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<ErrorKind, E> {
+                ERROR_KINDS
+                    .into_iter()
+                    .find(|kind| kind.description() == value)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(ErrorKindVisitor)
+    }
+}
+
+// The ID enums derive their serde implementations with `remote = "Self"`, which turns them
+// into inherent functions these wrap so an `Unknown` holding a defined ID is handled as that ID.
+macro_rules! normalized {
+    ($($id:ident => $normalize:expr),* $(,)?) => {
+        $(
+            impl Serialize for $id {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    $id::serialize(&$normalize(*self), serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $id {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$id, D::Error> {
+                    $id::deserialize(deserializer).map($normalize)
+                }
+            }
+        )*
+    };
+}
+
+normalized! {
+    ActionID => |id| ActionID::from(u8::from(id)),
+    AppAttributeID => |id| AppAttributeID::from(u8::from(id)),
+    CategoryID => |id| CategoryID::from(u8::from(id)),
+    CommandID => |id| CommandID::from(u8::from(id)),
+    EventID => |id| EventID::from(u8::from(id)),
+    NotificationAttributeID => |id| NotificationAttributeID::from(u8::from(id)),
+    RequestedAttribute => |attribute| match attribute {
+        RequestedAttribute::Unknown(id) => {
+            RequestedAttribute::try_from((NotificationAttributeID::from(id), None)).unwrap_or(attribute)
+        }
+        attribute => attribute,
+    },
+}
+
+impl Serialize for DateTime {
+    /// Serializes a `DateTime` as `yyyyMMdd'T'HHmmSS`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::date::DateTime;
+    /// let date = DateTime::new(2024, 2, 29, 13, 5, 0).unwrap();
+    ///
+    /// assert_eq!(serde_json::to_string(&date).unwrap(), r#""20240229T130500""#);
+    /// assert_eq!(serde_json::from_str::<DateTime>(r#""20240229T130500""#).unwrap(), date);
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        struct DateTimeVisitor;
+
+        impl Visitor<'_> for DateTimeVisitor {
+            type Value = DateTime;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a yyyyMMdd'T'HHmmSS date")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<DateTime, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(DateTimeVisitor)
+    }
+}
+
+impl Serialize for GetNotificationAttributesResponseRef<'_> {
+    /// Serializes a `GetNotificationAttributesResponseRef` the same way as a `GetNotificationAttributesResponse`
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::data_source::GetNotificationAttributesResponseRef;
+    /// let bytes: [u8; 9] = [0, 1, 0, 0, 0, 1, 1, 0, 104];
+    /// let (_, response) = GetNotificationAttributesResponseRef::parse(&bytes).unwrap();
+    ///
+    /// assert_eq!(
+    ///     serde_json::to_string(&response).unwrap(),
+    ///     r#"{"notification_uid":1,"attribute_list":[{"id":"Title","length":1,"value":"h"}]}"#,
+    /// );
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Attributes<'a>(GetNotificationAttributesResponseRef<'a>);
+
+        impl Serialize for Attributes<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.attributes())
+            }
+        }
+
+        let mut state = serializer.serialize_struct("GetNotificationAttributesResponseRef", 2)?;
+        state.serialize_field("notification_uid", &self.notification_uid)?;
+        state.serialize_field("attribute_list", &Attributes(*self))?;
+        state.end()
+    }
+}
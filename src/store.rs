@@ -0,0 +1,737 @@
+//! ## Store
+//!
+//! The `NotificationStore` mirrors the set of notifications currently shown on the iOS
+//! device. Feed it every `Notification` received on the Notification Source and every
+//! `GetNotificationAttributesResponse` received on the Data Source, it keeps the latest
+//! state of each notification and reports what changed, either as the return value of
+//! `apply` and `merge` or through the callbacks registered with `subscribe`.
+//!
+//! The `CategorySummary` aggregates the per category counts reported alongside each
+//! `Notification` into totals suitable for a badge or watch face complication.
+//!
+//! The `AppRegistry` caches the display names of apps so each one is only requested once.
+//!
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use nom::{
+    bytes::streaming::{take, take_till},
+    number::streaming::{le_u16, le_u8},
+    sequence::terminated,
+    IResult,
+};
+
+use crate::attributes::app::AppAttributeID;
+use crate::attributes::category::CategoryID;
+use crate::attributes::command::CommandID;
+use crate::attributes::event::EventID;
+use crate::characteristics::control_point::GetAppAttributesRequest;
+use crate::characteristics::data_source::{GetAppAttributesResponse, GetNotificationAttributesResponse};
+use crate::characteristics::notification_source::Notification;
+use crate::client::{ClientEvent, ResolvedNotification};
+use crate::error::utf8;
+use crate::Error;
+
+/// A change made to a `NotificationStore`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StoreChange {
+    Added(ResolvedNotification),
+    /// A notification was modified or received new attributes.
+    Modified { previous: ResolvedNotification, current: ResolvedNotification },
+    Removed(ResolvedNotification),
+}
+
+impl StoreChange {
+    /// Returns the UID of the notification that changed.
+    pub fn notification_uid(&self) -> u32 {
+        match self {
+            StoreChange::Added(notification) => notification.notification.notification_uid,
+            StoreChange::Modified { current, .. } => current.notification.notification_uid,
+            StoreChange::Removed(notification) => notification.notification.notification_uid,
+        }
+    }
+}
+
+/// A callback registered with `NotificationStore::subscribe`.
+type Subscriber = Box<dyn FnMut(&StoreChange)>;
+
+/// The `NotificationStore` type. See [the module level documentation](index.html) for more.
+///
+/// Only the notifications are serialized, callbacks registered with `subscribe` aren't.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotificationStore {
+    notifications: BTreeMap<u32, ResolvedNotification>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    subscribers: Vec<Subscriber>,
+}
+
+impl fmt::Debug for NotificationStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NotificationStore")
+            .field("notifications", &self.notifications)
+            .field("subscribers", &self.subscribers.len())
+            .finish()
+    }
+}
+
+impl NotificationStore {
+    pub fn new() -> NotificationStore {
+        NotificationStore::default()
+    }
+
+    /// Registers a callback that is called with every change made to the store
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::NotificationStore;
+    /// let mut store = NotificationStore::new();
+    /// let changed = Rc::new(RefCell::new(Vec::new()));
+    ///
+    /// let log = changed.clone();
+    /// store.subscribe(move |change| log.borrow_mut().push(change.notification_uid()));
+    ///
+    /// store.apply(Notification::parse(&[0, 0, 4, 1, 7, 0, 0, 0]).unwrap().1);
+    /// store.apply(Notification::parse(&[2, 0, 4, 0, 7, 0, 0, 0]).unwrap().1);
+    ///
+    /// assert_eq!(*changed.borrow(), vec![7, 7]);
+    /// ```
+    pub fn subscribe(&mut self, subscriber: impl FnMut(&StoreChange) + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    /// Applies an event received on the Notification Source and returns the resulting change
+    ///
+    /// An added event for a notification that is already stored, or a modified event for one
+    /// that isn't, is treated as the state the device reports rather than rejected. Events with
+    /// an unknown `EventID` and removals of notifications that aren't stored are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::{NotificationStore, StoreChange};
+    /// let mut store = NotificationStore::new();
+    ///
+    /// let change = store.apply(Notification::parse(&[0, 0, 1, 1, 7, 0, 0, 0]).unwrap().1);
+    /// assert!(matches!(change, Some(StoreChange::Added(_))));
+    /// assert_eq!(store.len(), 1);
+    ///
+    /// let change = store.apply(Notification::parse(&[2, 0, 1, 0, 7, 0, 0, 0]).unwrap().1);
+    /// assert!(matches!(change, Some(StoreChange::Removed(_))));
+    /// assert!(store.is_empty());
+    /// ```
+    pub fn apply(&mut self, notification: Notification) -> Option<StoreChange> {
+        let uid = notification.notification_uid;
+
+        let change = match notification.event_id {
+            EventID::NotificationAdded | EventID::NotificationModified => match self.notifications.get_mut(&uid) {
+                Some(stored) => {
+                    let previous = stored.clone();
+                    stored.notification = notification;
+
+                    match *stored != previous {
+                        true => Some(StoreChange::Modified { previous, current: stored.clone() }),
+                        false => None,
+                    }
+                }
+                None => {
+                    let stored = ResolvedNotification {
+                        notification,
+                        attributes: Vec::new(),
+                    };
+
+                    self.notifications.insert(uid, stored.clone());
+                    Some(StoreChange::Added(stored))
+                }
+            },
+            EventID::NotificationRemoved => self.notifications.remove(&uid).map(StoreChange::Removed),
+            EventID::Unknown(_) => None,
+        };
+
+        self.notify(change)
+    }
+
+    /// Merges the attributes of a response into the stored notification and returns the resulting change
+    ///
+    /// Attributes replace any previously stored attribute with the same ID. Responses for
+    /// notifications that aren't stored, such as ones removed while the request was in flight,
+    /// are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::data_source::GetNotificationAttributesResponse;
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::NotificationStore;
+    /// let mut store = NotificationStore::new();
+    /// store.apply(Notification::parse(&[0, 0, 1, 1, 7, 0, 0, 0]).unwrap().1);
+    ///
+    /// let (_, response) = GetNotificationAttributesResponse::parse(&[0, 7, 0, 0, 0, 1, 3, 0, 66, 111, 98]).unwrap();
+    /// store.merge(response);
+    ///
+    /// assert_eq!(store.get(7).unwrap().title(), Some("Bob"));
+    /// ```
+    pub fn merge(&mut self, response: GetNotificationAttributesResponse) -> Option<StoreChange> {
+        let stored = self.notifications.get_mut(&response.notification_uid)?;
+        let previous = stored.clone();
+
+        for attribute in response.attribute_list {
+            match stored.attributes.iter_mut().find(|stored| stored.id == attribute.id) {
+                Some(stored) => *stored = attribute,
+                None => stored.attributes.push(attribute),
+            }
+        }
+
+        let change = match *stored != previous {
+            true => Some(StoreChange::Modified { previous, current: stored.clone() }),
+            false => None,
+        };
+
+        self.notify(change)
+    }
+
+    /// Removes every notification, such as after the iOS device disconnects, reporting each as removed.
+    pub fn clear(&mut self) {
+        let notifications = core::mem::take(&mut self.notifications);
+
+        for (_, notification) in notifications {
+            self.notify(Some(StoreChange::Removed(notification)));
+        }
+    }
+
+    pub fn get(&self, notification_uid: u32) -> Option<&ResolvedNotification> {
+        self.notifications.get(&notification_uid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.notifications.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifications.is_empty()
+    }
+
+    /// Returns an iterator over the notifications ordered by their UID.
+    pub fn iter(&self) -> impl Iterator<Item = &ResolvedNotification> {
+        self.notifications.values()
+    }
+
+    /// Returns an iterator over the notifications from oldest to newest
+    ///
+    /// Notifications without a `Date` attribute come last, in the order of their UID.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::data_source::GetNotificationAttributesResponse;
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::NotificationStore;
+    /// let mut store = NotificationStore::new();
+    ///
+    /// for uid in [1, 2, 3] {
+    ///     store.apply(Notification::parse(&[0, 0, 4, 1, uid, 0, 0, 0]).unwrap().1);
+    /// }
+    ///
+    /// // Notification 1 arrived a day after notification 2
+    /// let date = *b"20240229T130500";
+    /// store.merge(GetNotificationAttributesResponse::parse(&[&[0, 1, 0, 0, 0, 5, 15, 0][..], &date].concat()).unwrap().1);
+    /// let date = *b"20240228T130500";
+    /// store.merge(GetNotificationAttributesResponse::parse(&[&[0, 2, 0, 0, 0, 5, 15, 0][..], &date].concat()).unwrap().1);
+    ///
+    /// let uids: Vec<u32> = store.iter_by_date().map(|n| n.notification.notification_uid).collect();
+    /// assert_eq!(uids, vec![2, 1, 3]);
+    /// ```
+    pub fn iter_by_date(&self) -> impl Iterator<Item = &ResolvedNotification> {
+        let mut notifications: Vec<&ResolvedNotification> = self.notifications.values().collect();
+
+        // The sort is stable so notifications with the same date keep their UID order.
+        notifications.sort_by_key(|notification| {
+            let date = notification.date();
+            (date.is_none(), date)
+        });
+
+        notifications.into_iter()
+    }
+
+    /// Returns an iterator over the notifications grouped by `CategoryID`, in the order
+    /// the categories are defined by ANCS and then by UID.
+    pub fn iter_by_category(&self) -> impl Iterator<Item = &ResolvedNotification> {
+        let mut notifications: Vec<&ResolvedNotification> = self.notifications.values().collect();
+
+        notifications.sort_by_key(|notification| u8::from(notification.notification.category_id));
+
+        notifications.into_iter()
+    }
+
+    /// Returns an iterator over the notifications in a single category.
+    pub fn category(&self, category_id: CategoryID) -> impl Iterator<Item = &ResolvedNotification> {
+        self.notifications
+            .values()
+            .filter(move |notification| notification.notification.category_id == category_id)
+    }
+
+    fn notify(&mut self, change: Option<StoreChange>) -> Option<StoreChange> {
+        if let Some(change) = &change {
+            for subscriber in self.subscribers.iter_mut() {
+                subscriber(change);
+            }
+        }
+
+        change
+    }
+}
+
+/// A category whose reported `category_count` disagrees with the notifications tracked in it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategoryMismatch {
+    pub category_id: CategoryID,
+    /// The latest `category_count` reported by the iOS device.
+    pub reported: u8,
+    /// The number of notifications in the category that have been added and not removed.
+    pub tracked: usize,
+}
+
+/// Aggregates the `category_count` of every `Notification` into per category totals.
+///
+/// Along with the latest count reported for each category the summary tracks the UIDs it
+/// has seen added and removed, so it can detect when the two disagree, for example after
+/// a Notification Source event was missed.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategorySummary {
+    /// The latest `category_count` keyed by the raw `CategoryID`.
+    counts: BTreeMap<u8, u8>,
+    categories: BTreeMap<u32, CategoryID>,
+}
+
+impl CategorySummary {
+    pub fn new() -> CategorySummary {
+        CategorySummary::default()
+    }
+
+    /// Applies an event received on the Notification Source
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::category::CategoryID;
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::CategorySummary;
+    /// let mut summary = CategorySummary::new();
+    ///
+    /// summary.apply(&Notification::parse(&[0, 0, 2, 1, 1, 0, 0, 0]).unwrap().1);
+    /// summary.apply(&Notification::parse(&[0, 0, 2, 2, 2, 0, 0, 0]).unwrap().1);
+    /// summary.apply(&Notification::parse(&[2, 0, 2, 1, 1, 0, 0, 0]).unwrap().1);
+    ///
+    /// assert_eq!(summary.count(CategoryID::MissedCall), 1);
+    /// assert!(summary.is_consistent());
+    /// ```
+    pub fn apply(&mut self, notification: &Notification) {
+        let category_id = notification.category_id;
+
+        self.counts.insert(category_id.into(), notification.category_count);
+
+        match notification.event_id {
+            EventID::NotificationAdded | EventID::NotificationModified => {
+                self.categories.insert(notification.notification_uid, category_id);
+            }
+            EventID::NotificationRemoved => {
+                self.categories.remove(&notification.notification_uid);
+            }
+            EventID::Unknown(_) => {}
+        }
+    }
+
+    /// Returns the latest `category_count` reported for a category.
+    pub fn count(&self, category_id: CategoryID) -> u8 {
+        self.counts.get(&category_id.into()).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of notifications tracked in a category.
+    pub fn tracked(&self, category_id: CategoryID) -> usize {
+        self.categories.values().filter(|&&tracked| tracked == category_id).count()
+    }
+
+    /// Returns an iterator over the categories with a non-zero count, in the order the
+    /// categories are defined by ANCS.
+    pub fn counts(&self) -> impl Iterator<Item = (CategoryID, u8)> + '_ {
+        self.counts
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .map(|(&id, &count)| (CategoryID::from(id), count))
+    }
+
+    /// Returns an iterator over the categories whose reported count disagrees with the
+    /// notifications tracked in them
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::category::CategoryID;
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::{CategoryMismatch, CategorySummary};
+    /// let mut summary = CategorySummary::new();
+    ///
+    /// // The device reports 3 social notifications but only one was seen
+    /// summary.apply(&Notification::parse(&[0, 0, 4, 3, 1, 0, 0, 0]).unwrap().1);
+    ///
+    /// let mismatches: Vec<CategoryMismatch> = summary.mismatches().collect();
+    /// assert_eq!(mismatches, vec![CategoryMismatch { category_id: CategoryID::Social, reported: 3, tracked: 1 }]);
+    /// ```
+    pub fn mismatches(&self) -> impl Iterator<Item = CategoryMismatch> + '_ {
+        let mut ids: Vec<u8> = self.counts.keys().copied().collect();
+        ids.extend(self.categories.values().map(|&category_id| u8::from(category_id)));
+        ids.sort_unstable();
+        ids.dedup();
+
+        ids.into_iter().filter_map(move |id| {
+            let category_id = CategoryID::from(id);
+            let reported = self.count(category_id);
+            let tracked = self.tracked(category_id);
+
+            match usize::from(reported) == tracked {
+                true => None,
+                false => Some(CategoryMismatch {
+                    category_id,
+                    reported,
+                    tracked,
+                }),
+            }
+        })
+    }
+
+    /// Determines if every reported count matches the notifications tracked in its category.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches().next().is_none()
+    }
+
+    /// Forgets every count and tracked notification, such as after the iOS device disconnects.
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.categories.clear();
+    }
+}
+
+impl fmt::Display for CategorySummary {
+    /// Formats the non-zero counts as a short human readable summary
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::notification_source::Notification;
+    /// # use ancs::store::CategorySummary;
+    /// let mut summary = CategorySummary::new();
+    ///
+    /// summary.apply(&Notification::parse(&[0, 0, 4, 14, 1, 0, 0, 0]).unwrap().1);
+    /// summary.apply(&Notification::parse(&[0, 0, 2, 3, 2, 0, 0, 0]).unwrap().1);
+    /// summary.apply(&Notification::parse(&[0, 0, 3, 2, 3, 0, 0, 0]).unwrap().1);
+    ///
+    /// assert_eq!(summary.to_string(), "3 missed calls, 2 voicemails, 14 social");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (category_id, count)) in self.counts().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+
+            let (singular, plural) = match category_id {
+                CategoryID::Other => ("other", "other"),
+                CategoryID::IncomingCall => ("incoming call", "incoming calls"),
+                CategoryID::MissedCall => ("missed call", "missed calls"),
+                CategoryID::Voicemail => ("voicemail", "voicemails"),
+                CategoryID::Social => ("social", "social"),
+                CategoryID::Schedule => ("schedule", "schedule"),
+                CategoryID::Email => ("email", "emails"),
+                CategoryID::News => ("news", "news"),
+                CategoryID::HealthAndFitness => ("health and fitness", "health and fitness"),
+                CategoryID::BusinessAndFinance => ("business and finance", "business and finance"),
+                CategoryID::Location => ("location", "location"),
+                CategoryID::Entertainment => ("entertainment", "entertainment"),
+                CategoryID::Unknown(id) => {
+                    write!(f, "{} in category {}", count, id)?;
+                    continue;
+                }
+            };
+
+            match count {
+                1 => write!(f, "1 {}", singular)?,
+                count => write!(f, "{} {}", count, plural)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Caches the display name of every app that posted a notification.
+///
+/// The registry learns which apps exist from the notifications it observes and hands out a
+/// `GetAppAttributesRequest` for each app identifier it doesn't have a display name for yet,
+/// so every app is only queried once. The cache can be persisted with `to_bytes` and reloaded
+/// with `from_bytes`, or with `serde` when that feature is enabled. Requests that are in flight
+/// aren't persisted.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppRegistry {
+    display_names: BTreeMap<String, String>,
+    /// App identifiers that need a request, in the order they were observed.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    missing: VecDeque<String>,
+    /// App identifiers whose request was handed out but not answered yet, in the order they were sent.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending: VecDeque<String>,
+}
+
+impl AppRegistry {
+    pub fn new() -> AppRegistry {
+        AppRegistry::default()
+    }
+
+    /// Returns the cached display name of an app.
+    pub fn display_name(&self, app_identifier: &str) -> Option<&str> {
+        self.display_names.get(app_identifier.trim_end_matches('\0')).map(String::as_str)
+    }
+
+    /// Caches the display name of an app, replacing any previous one
+    ///
+    /// Fails with `Error::InvalidAppIdentifier` if the app identifier contains a NULL byte
+    /// before its terminator, as ANCS can't carry it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::store::AppRegistry;
+    /// let mut registry = AppRegistry::new();
+    ///
+    /// assert_eq!(registry.insert("a\0", "hi"), Ok(()));
+    /// assert_eq!(registry.insert("a\0b", "hi"), Err(Error::InvalidAppIdentifier));
+    /// assert_eq!(registry.len(), 1);
+    /// ```
+    pub fn insert(&mut self, app_identifier: impl Into<String>, display_name: impl Into<String>) -> Result<(), Error> {
+        let mut app_identifier: String = app_identifier.into();
+        app_identifier.truncate(app_identifier.trim_end_matches('\0').len());
+
+        if app_identifier.contains('\0') {
+            return Err(Error::InvalidAppIdentifier);
+        }
+
+        self.missing.retain(|missing| *missing != app_identifier);
+        self.display_names.insert(app_identifier, display_name.into());
+
+        Ok(())
+    }
+
+    /// Notes that an app exists, queueing a request for its display name if it isn't cached
+    /// or already requested
+    ///
+    /// App identifiers that are empty or contain a NULL byte before their terminator are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::store::AppRegistry;
+    /// let mut registry = AppRegistry::new();
+    ///
+    /// registry.observe("com.apple.MobileSMS");
+    /// registry.observe("com.apple.MobileSMS");
+    ///
+    /// let request = registry.poll_request().unwrap();
+    /// assert_eq!(request.app_identifier, "com.apple.MobileSMS");
+    /// assert_eq!(registry.poll_request(), None);
+    /// ```
+    pub fn observe(&mut self, app_identifier: &str) {
+        let app_identifier = app_identifier.trim_end_matches('\0');
+
+        if app_identifier.is_empty()
+            || app_identifier.contains('\0')
+            || self.display_names.contains_key(app_identifier)
+            || self.missing.iter().chain(self.pending.iter()).any(|known| known == app_identifier)
+        {
+            return;
+        }
+
+        self.missing.push_back(app_identifier.to_string());
+    }
+
+    /// Returns an iterator over the app identifiers that still need a `GetAppAttributesRequest`.
+    pub fn missing(&self) -> impl Iterator<Item = &str> {
+        self.missing.iter().map(String::as_str)
+    }
+
+    /// Returns the next request for a missing display name and marks it as in flight.
+    pub fn poll_request(&mut self) -> Option<GetAppAttributesRequest> {
+        let app_identifier = self.missing.pop_front()?;
+        self.pending.push_back(app_identifier.clone());
+
+        Some(GetAppAttributesRequest::new(app_identifier, vec![AppAttributeID::DisplayName]))
+    }
+
+    /// Caches the display name carried by a response
+    ///
+    /// Apps without a display name are cached with an empty one, so they aren't requested again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::characteristics::data_source::GetAppAttributesResponse;
+    /// # use ancs::store::AppRegistry;
+    /// let mut registry = AppRegistry::new();
+    /// let (_, response) = GetAppAttributesResponse::parse(&[1, 97, 0, 0, 2, 0, 104, 105]).unwrap();
+    ///
+    /// registry.handle_response(&response);
+    ///
+    /// assert_eq!(registry.display_name("a"), Some("hi"));
+    /// ```
+    pub fn handle_response(&mut self, response: &GetAppAttributesResponse) {
+        let display_name = response
+            .attribute_list
+            .iter()
+            .find(|attribute| attribute.id == AppAttributeID::DisplayName)
+            .and_then(|attribute| attribute.value.clone())
+            .unwrap_or_default();
+        let app_identifier = response.app_identifier.trim_end_matches('\0');
+
+        self.pending.retain(|pending| pending != app_identifier);
+
+        // A parsed response ends its app identifier at the first NULL byte, so only a
+        // constructed one can be rejected, which is left uncached.
+        let _ = self.insert(app_identifier, display_name);
+    }
+
+    /// Handles an event produced by an `AncsClient`
+    ///
+    /// App identifiers of resolved notifications are observed, app attributes are cached and a
+    /// failed `GetAppAttributes` command forgets the request in flight for the same app, so it
+    /// is requested again once the app is next observed. Any requests the registry needs
+    /// afterwards are returned by `poll_request`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::attributes::app::AppAttributeID;
+    /// # use ancs::client::{AncsClient, ClientEvent};
+    /// # use ancs::store::AppRegistry;
+    /// let mut client = AncsClient::new();
+    /// let mut registry = AppRegistry::new();
+    ///
+    /// client.handle_notification_source(&[0, 0, 4, 1, 1, 0, 0, 0]).unwrap();
+    /// client.poll_transmit().unwrap();
+    /// client.handle_data_source(&[0, 1, 0, 0, 0, 0, 1, 0, 97, 1, 0, 0, 3, 0, 0, 5, 0, 0]).unwrap();
+    ///
+    /// while let Some(event) = client.poll_event() {
+    ///     registry.handle_event(&event);
+    /// }
+    ///
+    /// while let Some(request) = registry.poll_request() {
+    ///     client.fetch_app_attributes(request.app_identifier, request.attribute_ids).unwrap();
+    /// }
+    ///
+    /// assert_eq!(client.poll_transmit(), Some(vec![1, 97, 0, 0]));
+    /// client.handle_data_source(&[1, 97, 0, 0, 2, 0, 104, 105]).unwrap();
+    ///
+    /// while let Some(event) = client.poll_event() {
+    ///     registry.handle_event(&event);
+    /// }
+    ///
+    /// assert_eq!(registry.display_name("a"), Some("hi"));
+    /// ```
+    pub fn handle_event(&mut self, event: &ClientEvent) {
+        match event {
+            ClientEvent::NotificationAdded(notification) | ClientEvent::NotificationModified(notification) => {
+                if let Some(app_identifier) = notification.app_identifier() {
+                    self.observe(app_identifier);
+                }
+            }
+            ClientEvent::AppAttributes(response) => self.handle_response(response),
+            ClientEvent::CommandFailed {
+                command_id: CommandID::GetAppAttributes,
+                app_identifier: Some(app_identifier),
+                ..
+            } => {
+                self.pending.retain(|pending| pending != app_identifier);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns every request that was handed out but not answered to the missing app
+    /// identifiers, such as after the iOS device disconnects.
+    pub fn retry_pending(&mut self) {
+        while let Some(app_identifier) = self.pending.pop_back() {
+            self.missing.push_front(app_identifier);
+        }
+    }
+
+    /// Serializes the cached display names into a compact binary format
+    ///
+    /// Each cached app is written as its NULL terminated app identifier followed by the
+    /// little endian `u16` length of its display name and the display name itself. Fails
+    /// with `Error::CapacityExceeded` if a display name is longer than `u16::MAX` bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::store::AppRegistry;
+    /// let mut registry = AppRegistry::new();
+    /// registry.insert("a", "hi").unwrap();
+    ///
+    /// let bytes = registry.to_bytes().unwrap();
+    /// assert_eq!(bytes, vec![97, 0, 2, 0, 104, 105]);
+    ///
+    /// let reloaded = AppRegistry::from_bytes(&bytes).unwrap();
+    /// assert_eq!(reloaded.display_name("a"), Some("hi"));
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        for (app_identifier, display_name) in &self.display_names {
+            let length = u16::try_from(display_name.len())
+                .map_err(|_| Error::CapacityExceeded { capacity: u16::MAX.into() })?;
+
+            bytes.extend_from_slice(app_identifier.as_bytes());
+            bytes.push(0);
+            bytes.extend_from_slice(&length.to_le_bytes());
+            bytes.extend_from_slice(display_name.as_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    /// Reloads the display names serialized by `to_bytes`
+    ///
+    /// Fails with `Error::Truncated` if `data` ends part way through an app.
+    pub fn from_bytes(data: &[u8]) -> Result<AppRegistry, Error> {
+        let mut registry = AppRegistry::new();
+        let mut i = data;
+
+        while !i.is_empty() {
+            let (rest, (app_identifier, display_name)) = app(i)?;
+
+            registry.insert(utf8(app_identifier)?, utf8(display_name)?)?;
+            i = rest;
+        }
+
+        Ok(registry)
+    }
+
+    /// Returns an iterator over every cached app identifier and display name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.display_names.iter().map(|(app_identifier, display_name)| (app_identifier.as_str(), display_name.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.display_names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.display_names.is_empty()
+    }
+}
+
+/// Parses a single app written by `AppRegistry::to_bytes`.
+fn app(i: &[u8]) -> IResult<&[u8], (&[u8], &[u8]), Error> {
+    let (i, app_identifier) = terminated(take_till(|b| b == 0), le_u8)(i)?;
+    let (i, length) = le_u16(i)?;
+    let (i, display_name) = take(length)(i)?;
+
+    Ok((i, (app_identifier, display_name)))
+}
@@ -0,0 +1,99 @@
+//! ## Transport
+//!
+//! The `AncsTransport` and `BlockingTransport` traits are the boundary between the clients
+//! in this crate and a Bluetooth stack. An implementation subscribes to the Notification Source and Data
+//! Source characteristics of a connected iOS device, hands out the values notified on
+//! them and writes to its Control Point, everything else is handled by the client.
+//!
+use alloc::vec::Vec;
+#[cfg(feature = "async")]
+use core::future::Future;
+use std::time::Duration;
+
+#[cfg(feature = "bluez")]
+pub mod bluez;
+#[cfg(feature = "btleplug")]
+pub mod btleplug;
+#[cfg(feature = "tokio")]
+pub mod memory;
+
+/// A value notified on one of the ANCS characteristics.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Packet {
+    NotificationSource(Vec<u8>),
+    DataSource(Vec<u8>),
+}
+
+/// The outcome of a `BlockingTransport::receive` call.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Received {
+    Packet(Packet),
+    /// The timeout passed before a value was notified.
+    TimedOut,
+    /// The iOS device has disconnected.
+    Disconnected,
+}
+
+/// The reason a Control Point write failed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum WriteError<E> {
+    /// The iOS device rejected the write with an ATT error code.
+    Att(u8),
+    Transport(E),
+}
+
+/// An async connection to the ANCS characteristics of an iOS device.
+#[cfg(feature = "async")]
+pub trait AncsTransport {
+    type Error;
+
+    /// Enables notifications on the Data Source and Notification Source.
+    ///
+    /// The Data Source should be subscribed first, as iOS starts sending Notification
+    /// Source events for existing notifications as soon as that subscription is made.
+    fn subscribe(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Waits for the next value notified on either characteristic, returning `None`
+    /// once the iOS device has disconnected.
+    ///
+    /// The future must be cancel-safe: `AsyncAncsClient` drops it when the command in flight
+    /// times out, so a value must only be taken from the underlying source in the poll that
+    /// returns it, or it is lost.
+    fn receive(&mut self) -> impl Future<Output = Result<Option<Packet>, Self::Error>> + Send;
+
+    /// Writes a request to the Control Point and waits for the write response.
+    fn write_control_point(&mut self, data: Vec<u8>) -> impl Future<Output = Result<(), WriteError<Self::Error>>> + Send;
+}
+
+/// A blocking connection to the ANCS characteristics of an iOS device.
+///
+/// Like `std::io::Read` and `std::io::Write`, every call blocks the calling thread until
+/// it completes.
+pub trait BlockingTransport {
+    type Error;
+
+    /// Enables notifications on the Data Source and Notification Source.
+    ///
+    /// The Data Source should be subscribed first, as iOS starts sending Notification
+    /// Source events for existing notifications as soon as that subscription is made.
+    fn subscribe(&mut self) -> Result<(), Self::Error>;
+
+    /// Blocks until the next value is notified on either characteristic, for at most
+    /// `timeout` if one is given.
+    ///
+    /// Clients pass the time left until the command in flight expires, so a transport
+    /// that can't honor it leaves them waiting on a lost response indefinitely.
+    fn receive(&mut self, timeout: Option<Duration>) -> Result<Received, Self::Error>;
+
+    /// Writes a request to the Control Point and blocks until the write response.
+    fn write_control_point(&mut self, data: Vec<u8>) -> Result<(), WriteError<Self::Error>>;
+}
+
+/// Extracts the ATT error code of a failed write from an error message, Bluetooth stacks
+/// like BlueZ only report it as part of one, such as "Operation failed with ATT error: 0xa1".
+#[cfg(any(feature = "bluez", feature = "btleplug"))]
+pub(crate) fn att_error(message: &str) -> Option<u8> {
+    let (_, code) = message.split_once("ATT error: 0x")?;
+
+    u8::from_str_radix(code.get(..2)?, 16).ok()
+}
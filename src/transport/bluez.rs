@@ -0,0 +1,215 @@
+//! ## BlueZ Transport
+//!
+//! An `AncsTransport` talking to bluetoothd over D-Bus. `BluezTransport::new` finds the
+//! `org.bluez.GattService1` object of the ANCS service on a connected device along with the
+//! `org.bluez.GattCharacteristic1` objects of its characteristics, `subscribe` calls
+//! `StartNotify` on them in the order iOS expects, and the values BlueZ reports through
+//! `PropertiesChanged` signals are handed to the client as they arrive.
+//!
+//! The `mock` module serves fake BlueZ objects on a peer-to-peer D-Bus connection, to test
+//! without bluetoothd or a Bluetooth adapter.
+//!
+use std::collections::HashMap;
+use std::format;
+use std::vec::Vec;
+
+use futures_util::stream::StreamExt;
+use zbus::fdo::{ManagedObjects, ObjectManagerProxy};
+use zbus::message::Type;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, Value};
+use zbus::{Connection, MatchRule, MessageStream};
+
+use crate::characteristics::control_point::CONTROL_POINT_UUID;
+use crate::characteristics::data_source::DATA_SOURCE_UUID;
+use crate::characteristics::notification_source::NOTIFICATION_SOURCE_UUID;
+use crate::transport::{att_error, AncsTransport, Packet, WriteError};
+use crate::APPLE_NOTIFICATION_CENTER_SERVICE_UUID;
+
+#[cfg(unix)]
+pub mod mock;
+
+const BLUEZ: &str = "org.bluez";
+const DEVICE: &str = "org.bluez.Device1";
+const GATT_SERVICE: &str = "org.bluez.GattService1";
+const GATT_CHARACTERISTIC: &str = "org.bluez.GattCharacteristic1";
+
+/// The `BluezTransport` type. See [the module level documentation](index.html) for more.
+///
+/// # Examples
+/// ```
+/// # use ancs::attributes::notification::RequestedAttribute;
+/// # use ancs::client::{AsyncAncsClient, ClientEvent};
+/// # use ancs::transport::bluez::{mock, BluezTransport};
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (connection, device) = mock::pair().await.unwrap();
+/// let transport = BluezTransport::new(connection, mock::DEVICE_PATH.try_into().unwrap()).await.unwrap();
+///
+/// let mut client = AsyncAncsClient::new(transport);
+/// client.subscribe().await.unwrap();
+///
+/// // A new incoming call with UID 1 and the attributes the client will fetch for it
+/// device.notify_notification_source(vec![0, 0b00011000, 1, 1, 1, 0, 0, 0]).await.unwrap();
+/// device.notify_data_source(vec![0, 1, 0, 0, 0, 0, 3, 0, 102, 111, 111, 1, 3, 0, 66, 111, 98, 3, 0, 0, 5, 0, 0]).await.unwrap();
+///
+/// match client.next_event().await {
+///     Some(Ok(ClientEvent::NotificationAdded(notification))) => assert_eq!(notification.title(), Some("Bob")),
+///     _ => panic!("expected a resolved notification"),
+/// }
+/// assert_eq!(device.try_next_write(), Some(vec![0, 1, 0, 0, 0, 0, 1, 128, 0, 3, 0, 2, 5]));
+///
+/// device.disconnect().await.unwrap();
+/// assert!(client.next_event().await.is_none());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BluezTransport {
+    connection: Connection,
+    device: OwnedObjectPath,
+    notification_source: OwnedObjectPath,
+    control_point: OwnedObjectPath,
+    data_source: OwnedObjectPath,
+    signals: Option<MessageStream>,
+}
+
+impl BluezTransport {
+    /// Creates a transport for the connected device at `device`, such as `/org/bluez/hci0/dev_00_11_22_33_44_55`.
+    ///
+    /// Fails with `zbus::Error::InterfaceNotFound` if the device doesn't offer ANCS or BlueZ
+    /// hasn't resolved its services yet.
+    pub async fn new(connection: Connection, device: ObjectPath<'_>) -> zbus::Result<BluezTransport> {
+        let objects = ObjectManagerProxy::builder(&connection)
+            .destination(BLUEZ)?
+            .path("/")?
+            .build()
+            .await?
+            .get_managed_objects()
+            .await?;
+
+        let service = find(&objects, device.as_str(), GATT_SERVICE, APPLE_NOTIFICATION_CENTER_SERVICE_UUID)?;
+
+        Ok(BluezTransport {
+            notification_source: find(&objects, service.as_str(), GATT_CHARACTERISTIC, NOTIFICATION_SOURCE_UUID)?,
+            control_point: find(&objects, service.as_str(), GATT_CHARACTERISTIC, CONTROL_POINT_UUID)?,
+            data_source: find(&objects, service.as_str(), GATT_CHARACTERISTIC, DATA_SOURCE_UUID)?,
+            device: device.into(),
+            connection,
+            signals: None,
+        })
+    }
+
+    /// Returns the D-Bus connection, ending the notifications received through the transport.
+    pub fn into_inner(self) -> Connection {
+        self.connection
+    }
+
+    async fn start_notify(&self, characteristic: &OwnedObjectPath) -> zbus::Result<()> {
+        self.connection
+            .call_method(Some(BLUEZ), characteristic.as_ref(), Some(GATT_CHARACTERISTIC), "StartNotify", &())
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl AncsTransport for BluezTransport {
+    type Error = zbus::Error;
+
+    async fn subscribe(&mut self) -> zbus::Result<()> {
+        // The signals are matched before subscribing so no value notified in between is lost.
+        if self.signals.is_none() {
+            self.signals = Some(signals(&self.connection, &self.device).await?);
+        }
+
+        self.start_notify(&self.data_source).await?;
+        self.start_notify(&self.notification_source).await
+    }
+
+    async fn receive(&mut self) -> zbus::Result<Option<Packet>> {
+        let signals = match &mut self.signals {
+            Some(signals) => signals,
+            None => self.signals.insert(signals(&self.connection, &self.device).await?),
+        };
+
+        // Cancel-safe, a signal is only taken from the stream in the poll that handles it.
+        while let Some(message) = signals.next().await {
+            let message = message?;
+            let header = message.header();
+            let body = message.body();
+            let (interface, changed, _): (&str, HashMap<&str, Value<'_>>, Vec<&str>) = body.deserialize()?;
+
+            let path = match header.path() {
+                Some(path) => path.as_str(),
+                None => continue,
+            };
+
+            match (interface, changed.get("Connected"), changed.get("Value")) {
+                (DEVICE, Some(Value::Bool(false)), _) if path == self.device.as_str() => return Ok(None),
+                (GATT_CHARACTERISTIC, _, Some(value)) => {
+                    let value = Vec::<u8>::try_from(value.try_clone()?)?;
+
+                    if path == self.notification_source.as_str() {
+                        return Ok(Some(Packet::NotificationSource(value)));
+                    }
+
+                    if path == self.data_source.as_str() {
+                        return Ok(Some(Packet::DataSource(value)));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn write_control_point(&mut self, data: Vec<u8>) -> Result<(), WriteError<zbus::Error>> {
+        let options = HashMap::from([("type", Value::from("request"))]);
+
+        self.connection
+            .call_method(
+                Some(BLUEZ),
+                self.control_point.as_ref(),
+                Some(GATT_CHARACTERISTIC),
+                "WriteValue",
+                &(data, options),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| match att_error(&e.to_string()) {
+                Some(att_error) => WriteError::Att(att_error),
+                None => WriteError::Transport(e),
+            })
+    }
+}
+
+/// Matches the `PropertiesChanged` signals of the device and every object below it.
+async fn signals(connection: &Connection, device: &OwnedObjectPath) -> zbus::Result<MessageStream> {
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .path_namespace(device.as_ref())?
+        .build();
+
+    MessageStream::for_match_rule(rule, connection, None).await
+}
+
+/// Returns the path of the object below `parent` implementing `interface` with the given UUID.
+fn find(objects: &ManagedObjects, parent: &str, interface: &str, uuid: &str) -> zbus::Result<OwnedObjectPath> {
+    let parent = format!("{}/", parent);
+
+    objects
+        .iter()
+        .find(|(path, interfaces)| {
+            let properties = interfaces.iter().find(|(name, _)| name.as_str() == interface);
+
+            path.as_str().starts_with(&parent)
+                && matches!(
+                    properties.and_then(|(_, properties)| properties.get("UUID")).map(|value| &**value),
+                    Some(Value::Str(value)) if value.as_str().eq_ignore_ascii_case(uuid)
+                )
+        })
+        .map(|(path, _)| path.clone())
+        .ok_or(zbus::Error::InterfaceNotFound)
+}
@@ -0,0 +1,239 @@
+//! ## Mock BlueZ
+//!
+//! Fake BlueZ objects for testing a `BluezTransport` without bluetoothd. `pair` serves a
+//! connected device offering ANCS on one end of a peer-to-peer D-Bus connection and returns
+//! the other end, along with a `MockDevice` that plays the part of the iOS device.
+//!
+//! As with BlueZ, values are only notified once `StartNotify` has been called on their
+//! characteristic.
+//!
+use std::collections::{HashMap, VecDeque};
+use std::format;
+use std::os::unix::net::UnixStream;
+use std::string::String;
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+use zbus::fdo::ObjectManager;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::{connection, interface, Connection, Guid};
+
+use crate::characteristics::control_point::CONTROL_POINT_UUID;
+use crate::characteristics::data_source::DATA_SOURCE_UUID;
+use crate::characteristics::notification_source::NOTIFICATION_SOURCE_UUID;
+use crate::APPLE_NOTIFICATION_CENTER_SERVICE_UUID;
+
+/// The path of the device served by `pair`.
+pub const DEVICE_PATH: &str = "/org/bluez/hci0/dev_00_11_22_33_44_55";
+
+const SERVICE_PATH: &str = "/org/bluez/hci0/dev_00_11_22_33_44_55/service0010";
+const NOTIFICATION_SOURCE_PATH: &str = "/org/bluez/hci0/dev_00_11_22_33_44_55/service0010/char0011";
+const CONTROL_POINT_PATH: &str = "/org/bluez/hci0/dev_00_11_22_33_44_55/service0010/char0014";
+const DATA_SOURCE_PATH: &str = "/org/bluez/hci0/dev_00_11_22_33_44_55/service0010/char0017";
+
+/// Creates a connection to fake BlueZ objects and the `MockDevice` controlling them
+///
+/// # Examples
+/// ```
+/// # use ancs::transport::{AncsTransport, Packet};
+/// # use ancs::transport::bluez::{mock, BluezTransport};
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (connection, device) = mock::pair().await.unwrap();
+/// let mut transport = BluezTransport::new(connection, mock::DEVICE_PATH.try_into().unwrap()).await.unwrap();
+/// transport.subscribe().await.unwrap();
+///
+/// device.notify_notification_source(vec![0, 0, 1, 1, 7, 0, 0, 0]).await.unwrap();
+/// assert_eq!(transport.receive().await.unwrap(), Some(Packet::NotificationSource(vec![0, 0, 1, 1, 7, 0, 0, 0])));
+///
+/// transport.write_control_point(vec![2, 7, 0, 0, 0, 0]).await.unwrap();
+/// assert_eq!(device.try_next_write(), Some(vec![2, 7, 0, 0, 0, 0]));
+/// # }
+/// ```
+pub async fn pair() -> zbus::Result<(Connection, MockDevice)> {
+    let (client, server) = UnixStream::pair()?;
+    let writes = Arc::new(Mutex::new(Writes::default()));
+
+    let characteristic = |uuid: &str| Characteristic {
+        uuid: uuid.to_lowercase(),
+        value: Vec::new(),
+        notifying: false,
+        writes: writes.clone(),
+    };
+
+    let server = connection::Builder::async_io_unix_stream(server)
+        .p2p()
+        .server(Guid::generate())?
+        .serve_at("/", ObjectManager)?
+        .serve_at(DEVICE_PATH, Device { connected: true })?
+        .serve_at(SERVICE_PATH, Service)?
+        .serve_at(NOTIFICATION_SOURCE_PATH, characteristic(NOTIFICATION_SOURCE_UUID))?
+        .serve_at(CONTROL_POINT_PATH, characteristic(CONTROL_POINT_UUID))?
+        .serve_at(DATA_SOURCE_PATH, characteristic(DATA_SOURCE_UUID))?
+        .build();
+    let client = connection::Builder::async_io_unix_stream(client).p2p().build();
+
+    let (client, server) = futures_util::future::try_join(client, server).await?;
+
+    Ok((client, MockDevice { connection: server, writes }))
+}
+
+/// The iOS device behind the fake BlueZ objects.
+#[derive(Debug)]
+pub struct MockDevice {
+    connection: Connection,
+    writes: Arc<Mutex<Writes>>,
+}
+
+impl MockDevice {
+    /// Notifies a value on the Notification Source.
+    pub async fn notify_notification_source(&self, data: impl Into<Vec<u8>>) -> zbus::Result<()> {
+        self.notify(NOTIFICATION_SOURCE_PATH, data.into()).await
+    }
+
+    /// Notifies a value on the Data Source.
+    pub async fn notify_data_source(&self, data: impl Into<Vec<u8>>) -> zbus::Result<()> {
+        self.notify(DATA_SOURCE_PATH, data.into()).await
+    }
+
+    /// Returns the next Control Point write if one has been made.
+    pub fn try_next_write(&self) -> Option<Vec<u8>> {
+        self.writes.lock().expect("writes are never poisoned").writes.pop_front()
+    }
+
+    /// Makes the next Control Point write fail with the ATT error code `att_error`, writes
+    /// are acknowledged otherwise
+    ///
+    /// # Examples
+    /// ```
+    /// # use ancs::Error;
+    /// # use ancs::attributes::action::ActionID;
+    /// # use ancs::characteristics::control_point::AncsErrorCode;
+    /// # use ancs::client::{AsyncAncsClient, ClientError};
+    /// # use ancs::transport::bluez::{mock, BluezTransport};
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let (connection, device) = mock::pair().await.unwrap();
+    /// let transport = BluezTransport::new(connection, mock::DEVICE_PATH.try_into().unwrap()).await.unwrap();
+    /// let mut client = AsyncAncsClient::new(transport);
+    ///
+    /// device.reject_next_write(0xA3);
+    /// assert!(matches!(
+    ///     client.perform_action(7, ActionID::Positive).await,
+    ///     Err(ClientError::Ancs(Error::ControlPoint(AncsErrorCode::ActionFailed))),
+    /// ));
+    /// # }
+    /// ```
+    pub fn reject_next_write(&self, att_error: u8) {
+        self.writes.lock().expect("writes are never poisoned").errors.push_back(att_error);
+    }
+
+    /// Reports the device as disconnected, as BlueZ does once the link is lost.
+    pub async fn disconnect(&self) -> zbus::Result<()> {
+        let device = self.connection.object_server().interface::<_, Device>(DEVICE_PATH).await?;
+        let mut state = device.get_mut().await;
+        state.connected = false;
+        state.connected_changed(device.signal_emitter()).await
+    }
+
+    async fn notify(&self, path: &str, value: Vec<u8>) -> zbus::Result<()> {
+        let characteristic = self.connection.object_server().interface::<_, Characteristic>(path).await?;
+        let mut state = characteristic.get_mut().await;
+        state.value = value;
+
+        if state.notifying {
+            state.value_changed(characteristic.signal_emitter()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Writes {
+    writes: VecDeque<Vec<u8>>,
+    errors: VecDeque<u8>,
+}
+
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.bluez.Error")]
+enum BluezError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    Failed(String),
+}
+
+struct Device {
+    connected: bool,
+}
+
+#[interface(name = "org.bluez.Device1")]
+impl Device {
+    #[zbus(property)]
+    fn connected(&self) -> bool {
+        self.connected
+    }
+}
+
+struct Service;
+
+#[interface(name = "org.bluez.GattService1")]
+impl Service {
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        APPLE_NOTIFICATION_CENTER_SERVICE_UUID.to_lowercase()
+    }
+
+    #[zbus(property)]
+    fn primary(&self) -> bool {
+        true
+    }
+}
+
+struct Characteristic {
+    uuid: String,
+    value: Vec<u8>,
+    notifying: bool,
+    writes: Arc<Mutex<Writes>>,
+}
+
+#[interface(name = "org.bluez.GattCharacteristic1")]
+impl Characteristic {
+    async fn start_notify(&mut self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) -> zbus::fdo::Result<()> {
+        self.notifying = true;
+        self.notifying_changed(&emitter).await?;
+
+        Ok(())
+    }
+
+    fn write_value(&mut self, value: Vec<u8>, _options: HashMap<String, OwnedValue>) -> Result<(), BluezError> {
+        let mut writes = self.writes.lock().expect("writes are never poisoned");
+        writes.writes.push_back(value);
+
+        match writes.errors.pop_front() {
+            Some(att_error) => Err(BluezError::Failed(format!("Operation failed with ATT error: 0x{:02x}", att_error))),
+            None => Ok(()),
+        }
+    }
+
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    #[zbus(property)]
+    fn service(&self) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(SERVICE_PATH).expect("the service path is valid")
+    }
+
+    #[zbus(property)]
+    fn value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    #[zbus(property)]
+    fn notifying(&self) -> bool {
+        self.notifying
+    }
+}
@@ -0,0 +1,310 @@
+//! ## btleplug Transport
+//!
+//! An `AncsTransport` for an iOS device connected through btleplug. `BtleplugTransport::new`
+//! locates the ANCS characteristics of the peripheral, `subscribe` enables their notifications
+//! in the order iOS expects, and the values notified on them are handed to the client as
+//! they arrive.
+//!
+//! The transport only talks to the peripheral through the `GattPeripheral` trait, which is
+//! implemented for every `btleplug::api::Peripheral` and can be implemented by a mock
+//! peripheral to test without a Bluetooth radio.
+//!
+use std::boxed::Box;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::vec::Vec;
+
+use btleplug::api::{Characteristic, ValueNotification, WriteType};
+use futures_util::stream::{Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::characteristics::control_point::CONTROL_POINT_UUID;
+use crate::characteristics::data_source::DATA_SOURCE_UUID;
+use crate::characteristics::notification_source::NOTIFICATION_SOURCE_UUID;
+use crate::transport::{att_error, AncsTransport, Packet, WriteError};
+use crate::APPLE_NOTIFICATION_CENTER_SERVICE_UUID;
+
+/// The values notified by a peripheral.
+pub type Notifications = Pin<Box<dyn Stream<Item = ValueNotification> + Send>>;
+
+/// The parts of `btleplug::api::Peripheral` used by `BtleplugTransport`
+///
+/// Write errors carrying an ATT error code in their message, as BlueZ reports them, are
+/// passed on to the client as that code.
+///
+/// # Examples
+/// ```
+/// # use std::collections::BTreeSet;
+/// # use btleplug::api::{CharPropFlags, Characteristic};
+/// # use futures_util::stream;
+/// # use uuid::Uuid;
+/// # use ancs::{Error, APPLE_NOTIFICATION_CENTER_SERVICE_UUID};
+/// # use ancs::attributes::action::ActionID;
+/// # use ancs::characteristics::control_point::{AncsErrorCode, CONTROL_POINT_UUID};
+/// # use ancs::characteristics::data_source::DATA_SOURCE_UUID;
+/// # use ancs::characteristics::notification_source::NOTIFICATION_SOURCE_UUID;
+/// # use ancs::client::{AsyncAncsClient, ClientError};
+/// # use ancs::transport::btleplug::{BtleplugTransport, GattPeripheral, Notifications};
+/// /// A peripheral that fails every action.
+/// struct MockPeripheral;
+///
+/// impl GattPeripheral for MockPeripheral {
+/// #   fn characteristics(&self) -> BTreeSet<Characteristic> {
+/// #       [NOTIFICATION_SOURCE_UUID, CONTROL_POINT_UUID, DATA_SOURCE_UUID]
+/// #           .map(|uuid| Characteristic {
+/// #               uuid: Uuid::parse_str(uuid).unwrap(),
+/// #               service_uuid: Uuid::parse_str(APPLE_NOTIFICATION_CENTER_SERVICE_UUID).unwrap(),
+/// #               properties: CharPropFlags::empty(),
+/// #               descriptors: BTreeSet::new(),
+/// #           })
+/// #           .into()
+/// #   }
+/// #
+/// #   async fn discover_services(&self) -> btleplug::Result<()> {
+/// #       Ok(())
+/// #   }
+/// #
+/// #   async fn subscribe(&self, _: &Characteristic) -> btleplug::Result<()> {
+/// #       Ok(())
+/// #   }
+/// #
+/// #   async fn notifications(&self) -> btleplug::Result<Notifications> {
+/// #       Ok(Box::pin(stream::empty()))
+/// #   }
+/// #
+///     async fn write(&self, _: &Characteristic, _: &[u8]) -> btleplug::Result<()> {
+///         Err(btleplug::Error::Other("Operation failed with ATT error: 0xa3".into()))
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let transport = BtleplugTransport::new(MockPeripheral).await.unwrap();
+/// let mut client = AsyncAncsClient::new(transport);
+///
+/// assert!(matches!(
+///     client.perform_action(7, ActionID::Positive).await,
+///     Err(ClientError::Ancs(Error::ControlPoint(AncsErrorCode::ActionFailed))),
+/// ));
+/// # }
+/// ```
+pub trait GattPeripheral: Send + Sync {
+    /// Returns the characteristics discovered so far.
+    fn characteristics(&self) -> BTreeSet<Characteristic>;
+
+    fn discover_services(&self) -> impl Future<Output = btleplug::Result<()>> + Send;
+
+    fn subscribe(&self, characteristic: &Characteristic) -> impl Future<Output = btleplug::Result<()>> + Send;
+
+    /// Writes to a characteristic, waiting for the write response.
+    fn write(&self, characteristic: &Characteristic, data: &[u8]) -> impl Future<Output = btleplug::Result<()>> + Send;
+
+    /// Returns a stream of the values notified on every subscribed characteristic.
+    fn notifications(&self) -> impl Future<Output = btleplug::Result<Notifications>> + Send;
+}
+
+impl<P: btleplug::api::Peripheral> GattPeripheral for P {
+    fn characteristics(&self) -> BTreeSet<Characteristic> {
+        btleplug::api::Peripheral::characteristics(self)
+    }
+
+    async fn discover_services(&self) -> btleplug::Result<()> {
+        btleplug::api::Peripheral::discover_services(self).await
+    }
+
+    async fn subscribe(&self, characteristic: &Characteristic) -> btleplug::Result<()> {
+        btleplug::api::Peripheral::subscribe(self, characteristic).await
+    }
+
+    async fn write(&self, characteristic: &Characteristic, data: &[u8]) -> btleplug::Result<()> {
+        btleplug::api::Peripheral::write(self, characteristic, data, WriteType::WithResponse).await
+    }
+
+    async fn notifications(&self) -> btleplug::Result<Notifications> {
+        btleplug::api::Peripheral::notifications(self).await
+    }
+}
+
+/// The `BtleplugTransport` type. See [the module level documentation](index.html) for more.
+///
+/// # Examples
+/// ```
+/// # use std::collections::BTreeSet;
+/// # use std::sync::Mutex;
+/// # use btleplug::api::{CharPropFlags, Characteristic, ValueNotification};
+/// # use futures_util::stream;
+/// # use uuid::Uuid;
+/// # use ancs::APPLE_NOTIFICATION_CENTER_SERVICE_UUID;
+/// # use ancs::characteristics::control_point::CONTROL_POINT_UUID;
+/// # use ancs::characteristics::data_source::DATA_SOURCE_UUID;
+/// # use ancs::characteristics::notification_source::NOTIFICATION_SOURCE_UUID;
+/// # use ancs::client::{AsyncAncsClient, ClientEvent};
+/// # use ancs::transport::btleplug::{BtleplugTransport, GattPeripheral, Notifications};
+/// /// An iOS device that has a single incoming call.
+/// struct MockPeripheral {
+///     subscribed: Mutex<Vec<Uuid>>,
+///     writes: Mutex<Vec<Vec<u8>>>,
+/// }
+///
+/// fn characteristic(uuid: &str) -> Characteristic {
+///     Characteristic {
+///         uuid: Uuid::parse_str(uuid).unwrap(),
+///         service_uuid: Uuid::parse_str(APPLE_NOTIFICATION_CENTER_SERVICE_UUID).unwrap(),
+///         properties: CharPropFlags::empty(),
+///         descriptors: BTreeSet::new(),
+///     }
+/// }
+///
+/// impl GattPeripheral for MockPeripheral {
+///     fn characteristics(&self) -> BTreeSet<Characteristic> {
+///         [NOTIFICATION_SOURCE_UUID, CONTROL_POINT_UUID, DATA_SOURCE_UUID].map(characteristic).into()
+///     }
+///
+///     async fn discover_services(&self) -> btleplug::Result<()> {
+///         Ok(())
+///     }
+///
+///     async fn subscribe(&self, characteristic: &Characteristic) -> btleplug::Result<()> {
+///         self.subscribed.lock().unwrap().push(characteristic.uuid);
+///         Ok(())
+///     }
+///
+///     async fn write(&self, _: &Characteristic, data: &[u8]) -> btleplug::Result<()> {
+///         self.writes.lock().unwrap().push(data.to_vec());
+///         Ok(())
+///     }
+///
+///     async fn notifications(&self) -> btleplug::Result<Notifications> {
+///         let notify = |uuid, value| ValueNotification { uuid: Uuid::parse_str(uuid).unwrap(), value };
+///
+///         Ok(Box::pin(stream::iter([
+///             notify(NOTIFICATION_SOURCE_UUID, vec![0, 0b00011000, 1, 1, 1, 0, 0, 0]),
+///             notify(DATA_SOURCE_UUID, vec![0, 1, 0, 0, 0, 0, 3, 0, 102, 111, 111, 1, 3, 0, 66, 111, 98, 3, 0, 0, 5, 0, 0]),
+///         ])))
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let peripheral = MockPeripheral { subscribed: Mutex::default(), writes: Mutex::default() };
+/// let transport = BtleplugTransport::new(peripheral).await.unwrap();
+///
+/// let mut client = AsyncAncsClient::new(transport);
+/// client.subscribe().await.unwrap();
+///
+/// match client.next_event().await {
+///     Some(Ok(ClientEvent::NotificationAdded(notification))) => assert_eq!(notification.title(), Some("Bob")),
+///     _ => panic!("expected a resolved notification"),
+/// }
+///
+/// let peripheral = client.into_inner().into_inner();
+///
+/// // The Data Source is subscribed first so no response can be missed
+/// assert_eq!(*peripheral.subscribed.lock().unwrap(), [DATA_SOURCE_UUID, NOTIFICATION_SOURCE_UUID].map(|uuid| Uuid::parse_str(uuid).unwrap()));
+/// assert_eq!(*peripheral.writes.lock().unwrap(), [vec![0, 1, 0, 0, 0, 0, 1, 128, 0, 3, 0, 2, 5]]);
+/// # }
+/// ```
+pub struct BtleplugTransport<P> {
+    peripheral: P,
+    notification_source: Characteristic,
+    control_point: Characteristic,
+    data_source: Characteristic,
+    notifications: Option<Notifications>,
+}
+
+impl<P: fmt::Debug> fmt::Debug for BtleplugTransport<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BtleplugTransport")
+            .field("peripheral", &self.peripheral)
+            .field("notification_source", &self.notification_source)
+            .field("control_point", &self.control_point)
+            .field("data_source", &self.data_source)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: GattPeripheral> BtleplugTransport<P> {
+    /// Creates a transport for a connected peripheral, discovering its services if that hasn't been done yet.
+    ///
+    /// Fails with `btleplug::Error::NoSuchCharacteristic` if the peripheral doesn't offer ANCS.
+    pub async fn new(peripheral: P) -> btleplug::Result<BtleplugTransport<P>> {
+        if peripheral.characteristics().is_empty() {
+            peripheral.discover_services().await?;
+        }
+
+        let characteristics = peripheral.characteristics();
+        let find = |uuid: &str| {
+            let uuid = parse_uuid(uuid);
+            let service_uuid = parse_uuid(APPLE_NOTIFICATION_CENTER_SERVICE_UUID);
+
+            characteristics
+                .iter()
+                .find(|characteristic| characteristic.uuid == uuid && characteristic.service_uuid == service_uuid)
+                .cloned()
+                .ok_or(btleplug::Error::NoSuchCharacteristic)
+        };
+
+        Ok(BtleplugTransport {
+            notification_source: find(NOTIFICATION_SOURCE_UUID)?,
+            control_point: find(CONTROL_POINT_UUID)?,
+            data_source: find(DATA_SOURCE_UUID)?,
+            peripheral,
+            notifications: None,
+        })
+    }
+
+    /// Returns the peripheral, ending the notifications received through the transport.
+    pub fn into_inner(self) -> P {
+        self.peripheral
+    }
+}
+
+impl<P: GattPeripheral> AncsTransport for BtleplugTransport<P> {
+    type Error = btleplug::Error;
+
+    async fn subscribe(&mut self) -> btleplug::Result<()> {
+        // The stream is opened before subscribing so no value notified in between is lost.
+        if self.notifications.is_none() {
+            self.notifications = Some(self.peripheral.notifications().await?);
+        }
+
+        self.peripheral.subscribe(&self.data_source).await?;
+        self.peripheral.subscribe(&self.notification_source).await
+    }
+
+    async fn receive(&mut self) -> btleplug::Result<Option<Packet>> {
+        let notifications = match &mut self.notifications {
+            Some(notifications) => notifications,
+            None => self.notifications.insert(self.peripheral.notifications().await?),
+        };
+
+        // Cancel-safe, a notification is only taken from the stream in the poll that returns it.
+        while let Some(notification) = notifications.next().await {
+            if notification.uuid == self.notification_source.uuid {
+                return Ok(Some(Packet::NotificationSource(notification.value)));
+            }
+
+            if notification.uuid == self.data_source.uuid {
+                return Ok(Some(Packet::DataSource(notification.value)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn write_control_point(&mut self, data: Vec<u8>) -> Result<(), WriteError<btleplug::Error>> {
+        self.peripheral
+            .write(&self.control_point, &data)
+            .await
+            .map_err(|e| match att_error(&e.to_string()) {
+                Some(att_error) => WriteError::Att(att_error),
+                None => WriteError::Transport(e),
+            })
+    }
+}
+
+fn parse_uuid(uuid: &str) -> Uuid {
+    Uuid::parse_str(uuid).expect("the ANCS UUIDs are valid")
+}
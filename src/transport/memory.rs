@@ -0,0 +1,202 @@
+//! ## Memory Transport
+//!
+//! An `AncsTransport` backed by tokio channels, standing in for a Bluetooth connection
+//! so clients can be tested without hardware. `pair` returns the transport along with
+//! a `MemoryDevice` that plays the part of the iOS device.
+//!
+//! The transport implements both `AncsTransport` and `BlockingTransport`, the blocking
+//! calls must not be made from within an async runtime.
+//!
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use tokio::sync::mpsc;
+
+use crate::transport::{AncsTransport, BlockingTransport, Packet, Received, WriteError};
+
+/// The other end of a memory transport was dropped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Disconnected;
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "disconnected")
+    }
+}
+
+impl std::error::Error for Disconnected {}
+
+/// Creates a connected `MemoryTransport` and `MemoryDevice`
+///
+/// # Examples
+/// ```
+/// # use ancs::transport::{AncsTransport, Packet};
+/// # use ancs::transport::memory;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let (mut transport, mut device) = memory::pair();
+///
+/// device.notify_notification_source(vec![0, 0, 1, 1, 7, 0, 0, 0]).unwrap();
+/// assert_eq!(transport.receive().await, Ok(Some(Packet::NotificationSource(vec![0, 0, 1, 1, 7, 0, 0, 0]))));
+///
+/// transport.write_control_point(vec![2, 7, 0, 0, 0, 0]).await.unwrap();
+/// assert_eq!(device.next_write().await, Some(vec![2, 7, 0, 0, 0, 0]));
+/// # }
+/// ```
+pub fn pair() -> (MemoryTransport, MemoryDevice) {
+    let (packet_tx, packet_rx) = mpsc::unbounded_channel();
+    let (write_tx, write_rx) = mpsc::unbounded_channel();
+    let write_errors = Arc::new(Mutex::new(VecDeque::new()));
+
+    let transport = MemoryTransport {
+        packets: packet_rx,
+        writes: write_tx,
+        write_errors: write_errors.clone(),
+    };
+    let device = MemoryDevice {
+        packets: packet_tx,
+        writes: write_rx,
+        write_errors,
+    };
+
+    (transport, device)
+}
+
+/// The `MemoryTransport` type. See [the module level documentation](index.html) for more.
+#[derive(Debug)]
+pub struct MemoryTransport {
+    packets: mpsc::UnboundedReceiver<Packet>,
+    writes: mpsc::UnboundedSender<Vec<u8>>,
+    write_errors: Arc<Mutex<VecDeque<u8>>>,
+}
+
+impl AncsTransport for MemoryTransport {
+    type Error = Disconnected;
+
+    async fn subscribe(&mut self) -> Result<(), Disconnected> {
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<Option<Packet>, Disconnected> {
+        // Cancel-safe, tokio's `recv` never takes a packet it doesn't return.
+        Ok(self.packets.recv().await)
+    }
+
+    async fn write_control_point(&mut self, data: Vec<u8>) -> Result<(), WriteError<Disconnected>> {
+        self.write(data)
+    }
+}
+
+impl BlockingTransport for MemoryTransport {
+    type Error = Disconnected;
+
+    fn subscribe(&mut self) -> Result<(), Disconnected> {
+        Ok(())
+    }
+
+    /// Blocks until the next packet, for at most `timeout` if one is given
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::time::Duration;
+    /// # use ancs::transport::{BlockingTransport, Packet, Received};
+    /// # use ancs::transport::memory;
+    /// let (mut transport, device) = memory::pair();
+    ///
+    /// assert_eq!(transport.receive(Some(Duration::from_millis(10))), Ok(Received::TimedOut));
+    ///
+    /// device.notify_data_source(vec![0, 7, 0, 0, 0]).unwrap();
+    /// assert_eq!(transport.receive(None), Ok(Received::Packet(Packet::DataSource(vec![0, 7, 0, 0, 0]))));
+    ///
+    /// drop(device);
+    /// assert_eq!(transport.receive(None), Ok(Received::Disconnected));
+    /// ```
+    fn receive(&mut self, timeout: Option<Duration>) -> Result<Received, Disconnected> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            if let Poll::Ready(packet) = self.packets.poll_recv(&mut cx) {
+                return Ok(packet.map_or(Received::Disconnected, Received::Packet));
+            }
+
+            match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(left) if !left.is_zero() => thread::park_timeout(left),
+                    _ => return Ok(Received::TimedOut),
+                },
+                None => thread::park(),
+            }
+        }
+    }
+
+    fn write_control_point(&mut self, data: Vec<u8>) -> Result<(), WriteError<Disconnected>> {
+        self.write(data)
+    }
+}
+
+impl MemoryTransport {
+    fn write(&mut self, data: Vec<u8>) -> Result<(), WriteError<Disconnected>> {
+        self.writes.send(data).map_err(|_| WriteError::Transport(Disconnected))?;
+
+        let att_error = self.write_errors.lock().expect("write errors are never poisoned").pop_front();
+
+        match att_error {
+            Some(att_error) => Err(WriteError::Att(att_error)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Wakes a thread blocked in `BlockingTransport::receive`.
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// The iOS device end of a memory transport, dropping it disconnects the transport.
+#[derive(Debug)]
+pub struct MemoryDevice {
+    packets: mpsc::UnboundedSender<Packet>,
+    writes: mpsc::UnboundedReceiver<Vec<u8>>,
+    write_errors: Arc<Mutex<VecDeque<u8>>>,
+}
+
+impl MemoryDevice {
+    /// Notifies a value on the Notification Source.
+    pub fn notify_notification_source(&self, data: impl Into<Vec<u8>>) -> Result<(), Disconnected> {
+        self.packets
+            .send(Packet::NotificationSource(data.into()))
+            .map_err(|_| Disconnected)
+    }
+
+    /// Notifies a value on the Data Source.
+    pub fn notify_data_source(&self, data: impl Into<Vec<u8>>) -> Result<(), Disconnected> {
+        self.packets.send(Packet::DataSource(data.into())).map_err(|_| Disconnected)
+    }
+
+    /// Waits for the next Control Point write, returning `None` once the transport is dropped.
+    pub async fn next_write(&mut self) -> Option<Vec<u8>> {
+        self.writes.recv().await
+    }
+
+    /// Returns the next Control Point write if one has already been made.
+    pub fn try_next_write(&mut self) -> Option<Vec<u8>> {
+        self.writes.try_recv().ok()
+    }
+
+    /// Makes the next Control Point write fail with the ATT error code `att_error`, writes
+    /// are acknowledged otherwise.
+    pub fn reject_next_write(&self, att_error: u8) {
+        self.write_errors.lock().expect("write errors are never poisoned").push_back(att_error);
+    }
+}